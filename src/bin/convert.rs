@@ -0,0 +1,158 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+
+use mini_png::bmp;
+use mini_png::conversion::ConversionOptions;
+use mini_png::decode_options::DecodeOptions;
+use mini_png::dithering::Dithering;
+use mini_png::encode_options::{Compression, EncodeOptions};
+use mini_png::mini_png::MiniPNG;
+use mini_png::netpbm::{self, NetpbmEncoding};
+use mini_png::pixel::PixelType;
+use mini_png::png_codec;
+use mini_png::quantization::Quantizer;
+use mini_png::resampling::Filter;
+
+fn extension_of(file_path: &Path) -> String {
+    file_path.extension()
+             .map(|extension| extension.to_string_lossy().to_lowercase())
+             .unwrap_or_default()
+}
+
+fn read_image(file_path: &Path, flags: &[String]) -> Result<MiniPNG> {
+    let preserve_layout = flags.iter().any(|flag| flag == "--preserve-layout");
+
+    match extension_of(file_path).as_str() {
+        "mp" => Ok(MiniPNG::from_file_with_options(file_path, &DecodeOptions { preserve_layout, ..DecodeOptions::default() })?.0),
+        "png" => Ok(png_codec::from_png_file(file_path)?),
+        "pbm" | "pgm" | "ppm" | "pnm" => Ok(netpbm::from_netpbm_file(file_path)?),
+        "bmp" => Ok(bmp::from_bmp_file(file_path)?),
+        extension => Err(anyhow!("Unable to read the image: the \"{}\" format is not supported.", extension))
+    }
+}
+
+//the Netpbm variant is chosen from the pixel type, not from the extension
+fn write_image(image: &MiniPNG, file_path: &Path, flags: &[String]) -> Result<()> {
+    let netpbm_encoding = if flags.iter().any(|flag| flag == "--ascii") { NetpbmEncoding::Ascii } else { NetpbmEncoding::Binary };
+    let compression = if flags.iter().any(|flag| flag == "--compress") { Compression::Deflate } else { Compression::None };
+    let checksums = flags.iter().any(|flag| flag == "--checksums");
+
+    match extension_of(file_path).as_str() {
+        "mp" => Ok(image.save_with_options(file_path, &EncodeOptions { compression, checksums })?),
+        "png" => Ok(png_codec::save_png(image, file_path)?),
+        "pbm" | "pgm" | "ppm" | "pnm" => Ok(netpbm::save_netpbm(image, file_path, netpbm_encoding)?),
+        "bmp" => Ok(bmp::save_bmp(image, file_path)?),
+        extension => Err(anyhow!("Unable to write the image: the \"{}\" format is not supported.", extension))
+    }
+}
+
+//the image is converted to another pixel type with --pixel-type=<0, 1, 2 or 3>
+fn convert_image(image: MiniPNG, flags: &[String]) -> Result<MiniPNG> {
+    let pixel_type_flag = flags.iter().find_map(|flag| flag.strip_prefix("--pixel-type="));
+    let max_colors_flag = flags.iter().find_map(|flag| flag.strip_prefix("--max-colors="));
+
+    let mut options = ConversionOptions::default();
+    if let Some(value) = max_colors_flag {
+        options.max_palette_entries = value.parse()?;
+    }
+
+    if flags.iter().any(|flag| flag == "--octree") {
+        options.quantizer = Quantizer::Octree;
+    }
+
+    if let Some(value) = flags.iter().find_map(|flag| flag.strip_prefix("--dither=")) {
+        options.dithering = match value {
+            "floyd-steinberg" => Dithering::FloydSteinberg,
+            "atkinson" => Dithering::Atkinson,
+            "bayer4" => Dithering::Bayer4x4,
+            "bayer8" => Dithering::Bayer8x8,
+            _ => return Err(anyhow!("Unable to convert the image: \"{}\" is not a dithering algorithm.", value))
+        };
+    }
+
+    match pixel_type_flag {
+        Some(value) => {
+            let pixel_type = PixelType::try_from(value.parse::<u8>()?)?;
+            Ok(image.convert(pixel_type, options))
+        },
+        None => Ok(image)
+    }
+}
+
+//the transforms are applied in this order: --crop=<row>,<column>,<width>,<height>, --flip=<horizontal or vertical>, --rotate=<90, 180 or 270>, --transpose, --resize=<width>x<height>
+fn transform_image(mut image: MiniPNG, flags: &[String]) -> Result<MiniPNG> {
+    if let Some(value) = flags.iter().find_map(|flag| flag.strip_prefix("--crop=")) {
+        let values = value.split(',').map(|value| value.parse::<u32>()).collect::<std::result::Result<Vec<u32>, _>>()?;
+        if values.len() != 4 {
+            return Err(anyhow!("Unable to crop the image: \"{}\" is not a region.", value));
+        }
+
+        image = image.crop(values[0], values[1], values[2], values[3])
+                     .ok_or_else(|| anyhow!("Unable to crop the image: the region is empty or goes out of bounds."))?;
+    }
+
+    if let Some(value) = flags.iter().find_map(|flag| flag.strip_prefix("--flip=")) {
+        image = match value {
+            "horizontal" => image.flip_horizontal(),
+            "vertical" => image.flip_vertical(),
+            _ => return Err(anyhow!("Unable to flip the image: \"{}\" is not a direction.", value))
+        };
+    }
+
+    if let Some(value) = flags.iter().find_map(|flag| flag.strip_prefix("--rotate=")) {
+        image = match value {
+            "90" => image.rotate90(),
+            "180" => image.rotate180(),
+            "270" => image.rotate270(),
+            _ => return Err(anyhow!("Unable to rotate the image: \"{}\" is not an angle.", value))
+        };
+    }
+
+    if flags.iter().any(|flag| flag == "--transpose") {
+        image = image.transpose();
+    }
+
+    if let Some(value) = flags.iter().find_map(|flag| flag.strip_prefix("--resize=")) {
+        let (width, height) = value.split_once('x')
+                                   .ok_or_else(|| anyhow!("Unable to resize the image: \"{}\" is not a size.", value))?;
+
+        let filter = match flags.iter().find_map(|flag| flag.strip_prefix("--filter=")) {
+            None | Some("nearest") => Filter::Nearest,
+            Some("bilinear") => Filter::Bilinear,
+            Some("lanczos") => Filter::Lanczos,
+            Some(value) => return Err(anyhow!("Unable to resize the image: \"{}\" is not a filter.", value))
+        };
+
+        image = image.resize(width.parse()?, height.parse()?, filter)
+                     .ok_or_else(|| anyhow!("Unable to resize the image: the size cannot be 0."))?;
+    }
+
+    Ok(image)
+}
+
+fn answer(input_path: &Path, output_path: &Path, flags: &[String]) -> Result<()> {
+    let image = transform_image(read_image(input_path, flags)?, flags)?;
+    let image = convert_image(image, flags)?;
+
+    write_image(&image, output_path, flags)
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 3 {
+        eprintln!("Not enough arguments: please provide the path of the file to convert and the path of the converted file.");
+        std::process::exit(1);
+    }
+
+    let input_path = Path::new(&args[1]);
+    let output_path = Path::new(&args[2]);
+    match answer(input_path, output_path, &args[3..]) {
+        Ok(_) => {},
+        Err(error) => {
+            eprintln!("Error while trying to convert the file: {}", error);
+            std::process::exit(1);
+        }
+    }
+}
@@ -1,47 +1,461 @@
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use anyhow::{anyhow, Result};
-use sdl2::event::Event;
+use sdl2::event::{Event, WindowEvent};
 use sdl2::keyboard::Keycode;
-use std::time::Duration;
+use sdl2::mouse::MouseButton;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::rect::{Point, Rect};
+use sdl2::render::{Texture, TextureCreator};
+use sdl2::video::WindowContext;
 
 use mini_png::mini_png::MiniPNG;
+use mini_png::render::{self, Framebuffer, RenderOptions, GLYPH_HEIGHT, GLYPH_WIDTH};
 
-pub fn display(image: &MiniPNG) -> Result<()> {
+const MIN_WINDOW_SIZE: (u32, u32) = (320, 240);
+const MAX_SCALE: u32 = 64; //for both zooming in and out
+const PAN_STEP: i32 = 32; //in screen pixels, for the arrow keys
+const BACKGROUND: (u8, u8, u8) = (64, 64, 64);
+const ERROR_COLOR: (u8, u8, u8) = (255, 128, 128);
+const OVERLAY_COLOR: (u8, u8, u8) = (32, 32, 32);
+const RELOAD_INTERVAL: u32 = 500; //in milliseconds, how often the displayed file is checked for changes
+const INSPECTOR_COLOR: (u8, u8, u8) = (255, 255, 255);
+const GRID_COLOR: (u8, u8, u8) = (128, 128, 128);
+const MIN_GRID_ZOOM: u32 = 4; //below it, the grid would hide most of the image
+const TEXT_SCALE: u32 = 2;
+const TEXT_MARGIN: u32 = 16;
+
+//how the image is placed in the window, the scale is zoom / shrink and one of them is always 1
+struct View {
+    zoom: u32,
+    shrink: u32,
+    offset: (i32, i32)
+}
+
+impl View {
+    //1:1 scale, centered
+    fn reset() -> View {
+        View { zoom: 1, shrink: 1, offset: (0, 0) }
+    }
+
+    //the largest integer scale at which the whole image fits in the window, centered
+    fn fit(image: &MiniPNG, (window_width, window_height): (u32, u32)) -> View {
+        let (image_width, image_height) = (image.get_image_width(), image.get_image_height());
+
+        if image_width <= window_width && image_height <= window_height {
+            let zoom = (window_width / image_width).min(window_height / image_height).min(MAX_SCALE);
+            View { zoom, shrink: 1, offset: (0, 0) }
+        } else {
+            let shrink = image_width.div_ceil(window_width).max(image_height.div_ceil(window_height)).min(MAX_SCALE);
+            View { zoom: 1, shrink, offset: (0, 0) }
+        }
+    }
+
+    fn zoom_in(&mut self) {
+        let previous = (self.zoom, self.shrink);
+
+        if self.shrink > 1 {
+            self.shrink -= 1;
+        } else {
+            self.zoom = (self.zoom + 1).min(MAX_SCALE);
+        }
+
+        self.rescale_offset(previous);
+    }
+
+    fn zoom_out(&mut self) {
+        let previous = (self.zoom, self.shrink);
+
+        if self.zoom > 1 {
+            self.zoom -= 1;
+        } else {
+            self.shrink = (self.shrink + 1).min(MAX_SCALE);
+        }
+
+        self.rescale_offset(previous);
+    }
+
+    //keeps the point of the image at the center of the window in place
+    fn rescale_offset(&mut self, (previous_zoom, previous_shrink): (u32, u32)) {
+        let rescale = |offset: i32| (offset as i64 * self.zoom as i64 * previous_shrink as i64 / (self.shrink as i64 * previous_zoom as i64)) as i32;
+
+        self.offset = (rescale(self.offset.0), rescale(self.offset.1));
+    }
+
+    //the image cannot be moved further than its edge reaching the center of the window
+    fn pan(&mut self, image: Option<&MiniPNG>, dx: i32, dy: i32) {
+        let Some(image) = image else {
+            return;
+        };
+
+        let (scaled_width, scaled_height) = render::scaled_size(image, &self.render_options(None));
+        let (max_x, max_y) = ((scaled_width / 2) as i32, (scaled_height / 2) as i32);
+
+        self.offset = ((self.offset.0 + dx).clamp(-max_x, max_x), (self.offset.1 + dy).clamp(-max_y, max_y));
+    }
+
+    fn render_options(&self, viewport: Option<(u32, u32)>) -> RenderOptions {
+        RenderOptions {
+            zoom: self.zoom,
+            shrink: self.shrink,
+            background: BACKGROUND,
+            viewport,
+            offset: self.offset,
+            grid: None
+        }
+    }
+}
+
+//the files given as arguments, the directories being replaced by the MiniPNG files they contain (sorted by name)
+fn collect_paths(args: &[String]) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::<PathBuf>::new();
+
+    for arg in args {
+        let path = PathBuf::from(arg);
+
+        if path.is_dir() {
+            let mut directory_paths = fs::read_dir(&path)?.map(|entry| entry.map(|entry| entry.path()))
+                                                          .collect::<std::io::Result<Vec<PathBuf>>>()?;
+            directory_paths.retain(|path| path.is_file() && path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("mp")));
+            directory_paths.sort();
+
+            paths.extend(directory_paths);
+        } else {
+            paths.push(path);
+        }
+    }
+
+    Ok(paths)
+}
+
+//the pixel described by the inspector is the one pinned with a right click, or else the one under the cursor
+#[derive(Default)]
+struct Inspector {
+    enabled: bool,
+    hovered: Option<(u32, u32)>,
+    pinned: Option<(u32, u32)>
+}
+
+impl Inspector {
+    fn inspected(&self) -> Option<(u32, u32)> {
+        if self.enabled { self.pinned.or(self.hovered) } else { None }
+    }
+}
+
+//what is compared to detect that a file has been rewritten, the size helps when the modification time is not precise enough
+type FileVersion = Option<(SystemTime, u64)>;
+
+fn file_version(path: &Path) -> FileVersion {
+    let metadata = fs::metadata(path).ok()?;
+
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+//the files being browsed, the current one is decoded when it is selected and again whenever it changes on disk
+struct Browser {
+    paths: Vec<PathBuf>, //not empty
+    index: usize,
+    image: Option<MiniPNG>, //the last version of the file that could be decoded
+    error: Option<String>, //why the current version of the file could not be decoded
+    version: FileVersion,
+    generation: u64 //changes whenever image is replaced, so that its texture is uploaded again
+}
+
+impl Browser {
+    fn new(paths: Vec<PathBuf>) -> Browser {
+        let mut browser = Browser { paths, index: 0, image: None, error: None, version: None, generation: 0 };
+        browser.select(0);
+
+        browser
+    }
+
+    fn select(&mut self, index: usize) {
+        self.index = index % self.paths.len();
+        self.image = None;
+        self.load();
+    }
+
+    //the previous image is kept if the file cannot be decoded anymore
+    fn load(&mut self) {
+        let path = &self.paths[self.index];
+        self.version = file_version(path);
+
+        match MiniPNG::from_file(path) {
+            Ok(image) => {
+                (self.image, self.error) = (Some(image), None);
+                self.generation += 1;
+            },
+            Err(error) => self.error = Some(error.to_string())
+        }
+    }
+
+    //returns whether the file has changed since it was last decoded
+    fn reload_if_changed(&mut self) -> bool {
+        if file_version(&self.paths[self.index]) == self.version {
+            return false;
+        }
+
+        self.load();
+        true
+    }
+
+    fn next(&mut self) {
+        self.select(self.index + 1);
+    }
+
+    fn previous(&mut self) {
+        self.select(self.index + self.paths.len() - 1);
+    }
+
+    fn file_name(&self) -> String {
+        let path = &self.paths[self.index];
+
+        path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("{} ({}/{}) - MiniPNG Viewer", self.file_name(), self.index + 1, self.paths.len())
+    }
+
+    //the pixel of the image (row, column) under the given point of the window
+    fn image_position(&self, view: &View, window_size: (u32, u32), (x, y): (i32, i32)) -> Option<(u32, u32)> {
+        self.image.as_ref()
+                  .and_then(|image| render::image_position(image, &view.render_options(Some(window_size)), y as i64, x as i64))
+    }
+
+    //the reason why the file cannot be displayed and the description of the inspected pixel, drawn over the image
+    fn overlays(&self, window_size: (u32, u32), inspected: Option<(u32, u32)>) -> Vec<Overlay> {
+        let mut overlays = Vec::new();
+
+        //the position may be out of bounds if the file has been reloaded since it was chosen
+        let inspected = inspected.zip(self.image.as_ref())
+                                 .and_then(|((x, y), image)| Some((x, y, image.get_pixel_at(x, y)?, image.get_rgb_at(x, y)?)));
+
+        if let Some((x, y, pixel, rgb)) = inspected {
+            let text = format!("Row {}, column {}\n{:?}\nRGB {:?}", x, y, pixel, rgb);
+            let box_width = text.lines().map(|line| line.len() as u32).max().unwrap_or(0) * GLYPH_WIDTH * TEXT_SCALE + 2 * TEXT_MARGIN;
+            let framebuffer = text_box(&text, box_width, INSPECTOR_COLOR);
+
+            overlays.push(Overlay { x: window_size.1.saturating_sub(framebuffer.get_height() + TEXT_MARGIN), y: TEXT_MARGIN, framebuffer });
+        }
+
+        if let Some(error) = &self.error {
+            let max_chars = (window_size.0.saturating_sub(4 * TEXT_MARGIN) / (GLYPH_WIDTH * TEXT_SCALE)) as usize;
+            let text = render::wrap_text(&format!("Unable to display {}: {}", self.file_name(), error), max_chars);
+
+            overlays.push(Overlay { x: TEXT_MARGIN, y: TEXT_MARGIN, framebuffer: text_box(&text, window_size.0.saturating_sub(2 * TEXT_MARGIN), ERROR_COLOR) });
+        }
+
+        overlays
+    }
+
+    //the image at the largest scale fitting in the window
+    fn fitted_view(&self, window_size: (u32, u32)) -> View {
+        match &self.image {
+            Some(image) => View::fit(image, window_size),
+            None => View::reset()
+        }
+    }
+}
+
+//a box drawn over the image, its top left corner being at row x and column y of the window
+struct Overlay {
+    x: u32,
+    y: u32,
+    framebuffer: Framebuffer
+}
+
+fn text_box(text: &str, width: u32, color: (u8, u8, u8)) -> Framebuffer {
+    let height = text.lines().count() as u32 * GLYPH_HEIGHT * TEXT_SCALE + 2 * TEXT_MARGIN;
+    let mut framebuffer = Framebuffer::new(width, height, OVERLAY_COLOR);
+    framebuffer.draw_text(TEXT_MARGIN, TEXT_MARGIN, text, color, TEXT_SCALE);
+
+    framebuffer
+}
+
+//the image at 1:1, or shrunk just enough to fit in the largest texture supported by the renderer (0 meaning no limit)
+fn texture_framebuffer(image: &MiniPNG, (max_width, max_height): (u32, u32)) -> Framebuffer {
+    let shrink = |length: u32, max_length: u32| if max_length == 0 { 1 } else { length.div_ceil(max_length) };
+    let shrink = shrink(image.get_image_width(), max_width).max(shrink(image.get_image_height(), max_height)).max(1);
+
+    render::render(image, &RenderOptions { shrink, ..RenderOptions::default() })
+}
+
+fn upload<'a>(texture_creator: &'a TextureCreator<WindowContext>, framebuffer: &Framebuffer) -> Result<Texture<'a>> {
+    let mut texture = texture_creator.create_texture_streaming(PixelFormatEnum::RGB24, framebuffer.get_width(), framebuffer.get_height())
+                                     .map_err(|err| anyhow!(err))?;
+
+    //the rows of the texture may be longer than the ones of the framebuffer
+    texture.with_lock(None, |buffer: &mut [u8], pitch: usize| {
+        for (texture_row, framebuffer_row) in buffer.chunks_mut(pitch).zip(framebuffer.as_bytes().chunks(framebuffer.pitch())) {
+            texture_row[..framebuffer_row.len()].copy_from_slice(framebuffer_row);
+        }
+    }).map_err(|err| anyhow!(err))?;
+
+    Ok(texture)
+}
+
+//the lines along the top and left edges of the pixels of the image visible in the window, as drawn by RenderOptions::grid
+fn grid_lines(image: &MiniPNG, view: &View, (window_width, window_height): (u32, u32)) -> Vec<(Point, Point)> {
+    let options = view.render_options(Some((window_width, window_height)));
+    let (top, left) = render::image_origin(image, &options);
+    let (scaled_width, scaled_height) = render::scaled_size(image, &options);
+
+    //the part of the image inside the window
+    let (first_row, last_row) = (top.max(0), (top + scaled_height as i64).min(window_height as i64) - 1);
+    let (first_column, last_column) = (left.max(0), (left + scaled_width as i64).min(window_width as i64) - 1);
+    if first_row > last_row || first_column > last_column {
+        return Vec::new();
+    }
+
+    let zoom = view.zoom as i64;
+    let rows = (0..image.get_image_height() as i64).map(|row| top + row * zoom)
+                                                    .filter(|x| (first_row..=last_row).contains(x))
+                                                    .map(|x| (Point::new(first_column as i32, x as i32), Point::new(last_column as i32, x as i32)));
+    let columns = (0..image.get_image_width() as i64).map(|column| left + column * zoom)
+                                                      .filter(|y| (first_column..=last_column).contains(y))
+                                                      .map(|y| (Point::new(y as i32, first_row as i32), Point::new(y as i32, last_row as i32)));
+
+    rows.chain(columns).collect()
+}
+
+pub fn display(paths: Vec<PathBuf>) -> Result<()> {
     let sdl_context = sdl2::init().unwrap();
     let video_subsystem = sdl_context.video().map_err(|err| anyhow!(err))?;
 
-    let window = video_subsystem.window("MiniPNG Viewer", image.get_image_width(), image.get_image_height())
+    let mut browser = Browser::new(paths);
+
+    //the window is sized for the first image at the largest scale fitting in most of the screen
+    let screen = video_subsystem.display_bounds(0).map_err(|err| anyhow!(err))?;
+    let initial_view = browser.fitted_view((screen.width() * 4 / 5, screen.height() * 4 / 5));
+    let (scaled_width, scaled_height) = match &browser.image {
+        Some(image) => render::scaled_size(image, &initial_view.render_options(None)),
+        None => MIN_WINDOW_SIZE
+    };
+
+    let mut window = video_subsystem.window(&browser.title(), scaled_width.max(MIN_WINDOW_SIZE.0), scaled_height.max(MIN_WINDOW_SIZE.1))
         .position_centered()
+        .resizable()
         .build()
         .map_err(|err| anyhow!(err))?;
+    window.set_minimum_size(MIN_WINDOW_SIZE.0, MIN_WINDOW_SIZE.1).map_err(|err| anyhow!(err))?;
 
     let mut canvas = window.into_canvas().build().map_err(|err| anyhow!(err))?;
+    let texture_creator = canvas.texture_creator();
 
     let mut event_pump = sdl_context.event_pump().map_err(|err| anyhow!(err))?;
 
+    let mut view = initial_view;
+    let mut inspector = Inspector::default();
+    let mut show_grid = false;
+    let mut redraw = true;
+
+    //the image is uploaded once, zooming and panning only change where SDL copies it (without smoothing the pixels)
+    let mut image_texture = None; //(generation of the image, texture)
+    let max_texture_size = (canvas.info().max_texture_width, canvas.info().max_texture_height);
+    sdl2::hint::set("SDL_RENDER_SCALE_QUALITY", "nearest");
+
+    //nothing is done until an event requires the window to be drawn again
     'running: loop {
-        for x in 0..image.get_image_height() {
-            for y in 0..image.get_image_width() {
-                let pixel = image.get_rgb_at(x, y).unwrap(); //safe unwrap since we cannot go out of bounds
-                
-                canvas.set_draw_color(pixel);
-                canvas.draw_point((y as i32, x as i32)).map_err(|err| anyhow!(err))?;
+        if redraw {
+            let window_size = canvas.output_size().map_err(|err| anyhow!(err))?;
+
+            canvas.set_draw_color(BACKGROUND);
+            canvas.clear();
+
+            if let Some(image) = &browser.image {
+                let texture = match &mut image_texture {
+                    Some((generation, texture)) if *generation == browser.generation => texture,
+                    image_texture => &mut image_texture.insert((browser.generation, upload(&texture_creator, &texture_framebuffer(image, max_texture_size))?)).1
+                };
+
+                let options = view.render_options(Some(window_size));
+                let (top, left) = render::image_origin(image, &options);
+                let (scaled_width, scaled_height) = render::scaled_size(image, &options);
+                canvas.copy(texture, None, Rect::new(left as i32, top as i32, scaled_width, scaled_height)).map_err(|err| anyhow!(err))?;
+
+                if show_grid && view.zoom >= MIN_GRID_ZOOM {
+                    canvas.set_draw_color(GRID_COLOR);
+
+                    for (start, end) in grid_lines(image, &view, window_size) {
+                        canvas.draw_line(start, end).map_err(|err| anyhow!(err))?;
+                    }
+                }
             }
+
+            for overlay in browser.overlays(window_size, inspector.inspected()) {
+                let texture = upload(&texture_creator, &overlay.framebuffer)?;
+                let target = Rect::new(overlay.y as i32, overlay.x as i32, overlay.framebuffer.get_width(), overlay.framebuffer.get_height());
+
+                canvas.copy(&texture, None, target).map_err(|err| anyhow!(err))?;
+            }
+
+            canvas.present();
+            redraw = false;
         }
-        
-        for event in event_pump.poll_iter() {
+
+        //the window is only woken up by events, or to check whether the file has changed (the zoom and pan are kept)
+        let first_event = event_pump.wait_event_timeout(RELOAD_INTERVAL);
+        if browser.reload_if_changed() {
+            redraw = true;
+        }
+
+        //the pending events are handled together, so that a burst of them only gives one redraw
+        for event in first_event.into_iter().chain(event_pump.poll_iter()) {
+            let window_size = canvas.output_size().map_err(|err| anyhow!(err))?;
+
+            //the inspected pixel follows the cursor, even while panning
+            if let Event::MouseMotion { x, y, .. } = event {
+                let hovered = browser.image_position(&view, window_size, (x, y));
+
+                if inspector.enabled && inspector.pinned.is_none() && hovered != inspector.hovered {
+                    redraw = true;
+                }
+
+                inspector.hovered = hovered;
+            }
+
             match event {
                 Event::Quit {..} |
                 Event::KeyDown { keycode: Some(Keycode::Escape), .. } => {
                     break 'running
                 },
-                _ => {}
+                Event::KeyDown { keycode: Some(Keycode::Plus | Keycode::Equals | Keycode::KpPlus), .. } => view.zoom_in(),
+                Event::KeyDown { keycode: Some(Keycode::Minus | Keycode::KpMinus), .. } => view.zoom_out(),
+                Event::KeyDown { keycode: Some(Keycode::PageDown | Keycode::Space | Keycode::N), .. } => {
+                    browser.next();
+                    view = browser.fitted_view(window_size);
+                    inspector.pinned = None;
+                    canvas.window_mut().set_title(&browser.title()).map_err(|err| anyhow!(err))?;
+                },
+                Event::KeyDown { keycode: Some(Keycode::PageUp | Keycode::Backspace | Keycode::P), .. } => {
+                    browser.previous();
+                    view = browser.fitted_view(window_size);
+                    inspector.pinned = None;
+                    canvas.window_mut().set_title(&browser.title()).map_err(|err| anyhow!(err))?;
+                },
+                Event::KeyDown { keycode: Some(Keycode::F), .. } => view = browser.fitted_view(window_size),
+                Event::KeyDown { keycode: Some(Keycode::Num1 | Keycode::Kp1 | Keycode::Num0 | Keycode::Kp0), .. } => view = View::reset(),
+                Event::KeyDown { keycode: Some(Keycode::Left), .. } => view.pan(browser.image.as_ref(), PAN_STEP, 0),
+                Event::KeyDown { keycode: Some(Keycode::Right), .. } => view.pan(browser.image.as_ref(), -PAN_STEP, 0),
+                Event::KeyDown { keycode: Some(Keycode::Up), .. } => view.pan(browser.image.as_ref(), 0, PAN_STEP),
+                Event::KeyDown { keycode: Some(Keycode::Down), .. } => view.pan(browser.image.as_ref(), 0, -PAN_STEP),
+                Event::KeyDown { keycode: Some(Keycode::I), .. } => (inspector.enabled, inspector.pinned) = (!inspector.enabled, None),
+                Event::KeyDown { keycode: Some(Keycode::G), .. } => show_grid = !show_grid,
+                Event::MouseButtonDown { mouse_btn: MouseButton::Right, .. } if inspector.enabled => {
+                    inspector.pinned = if inspector.pinned.is_some() { None } else { inspector.hovered };
+                },
+                Event::MouseWheel { y, .. } if y > 0 => view.zoom_in(),
+                Event::MouseWheel { y, .. } if y < 0 => view.zoom_out(),
+                Event::MouseMotion { mousestate, xrel, yrel, .. } if mousestate.is_mouse_button_pressed(MouseButton::Left) => view.pan(browser.image.as_ref(), xrel, yrel),
+                Event::Window { win_event: WindowEvent::SizeChanged(..) | WindowEvent::Exposed, .. } => {},
+                _ => continue
             }
-        }
 
-        canvas.present();
-        ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
+            redraw = true; //the view, the inspected pixel or the window changed
+        }
     }
 
     Ok(())
@@ -51,18 +465,21 @@ fn main() -> Result<()> {
     let args: Vec<String> = std::env::args().collect();
     
     if args.len() < 2 {
-        eprintln!("Not enough arguments: please provide the path of the file to parse.");
+        eprintln!("Not enough arguments: please provide the paths of the files (or directories) to display.");
         std::process::exit(1);
     }
 
-    let path = Path::new(&args[1]);
-    let image = MiniPNG::from_file(path)?;
+    let paths = collect_paths(&args[1..])?;
+    if paths.is_empty() {
+        eprintln!("Nothing to display: no MiniPNG file has been found.");
+        std::process::exit(1);
+    }
 
-    match display(&image) {
+    match display(paths) {
         Ok(_) => Ok(()),
         Err(error) => { 
-            eprintln!("Error while trying to parse the file: {}", error); 
+            eprintln!("Error while trying to display the files: {}", error); 
             std::process::exit(1);
         }
     }
-}
\ No newline at end of file
+}
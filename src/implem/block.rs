@@ -1,12 +1,42 @@
-use anyhow::{anyhow, Result};
+use super::error::{MiniPngError, Result};
 
-use super::{comment_block::CommentBlock, data_block::DataBlock, header_block::HeaderBlock, palette_block::PaletteBlock};
+use super::{checksum_block::ChecksumBlock, comment_block::CommentBlock, data_block::DataBlock, header_block::HeaderBlock, palette_block::PaletteBlock};
+
+pub const MAX_BLOCK_LENGTH: usize = u32::MAX as usize; //the length of a block is stored on 4 bytes
+//the size a Z block may inflate to when no limit is given (with TryFrom or RawBlock::parse), MiniPNG decoding uses the size of the image instead
+pub const DEFAULT_MAX_DATA_LENGTH: usize = 64 * 1024 * 1024;
 
 pub enum BlockContent {
     Header(HeaderBlock),
     Comment(CommentBlock),
     Data(DataBlock),
-    Palette(PaletteBlock)
+    Palette(PaletteBlock),
+    Checksum(ChecksumBlock),
+    Unknown { type_byte: u8, bytes: Vec<u8> } //ancillary blocks this implementation does not understand
+}
+
+impl BlockContent {
+    pub fn type_byte(&self) -> u8 {
+        match self {
+            BlockContent::Header(_) => b'H',
+            BlockContent::Comment(_) => b'C',
+            BlockContent::Data(it) => if it.is_compressed() { b'Z' } else { b'D' },
+            BlockContent::Palette(_) => b'P',
+            BlockContent::Checksum(_) => b'K',
+            BlockContent::Unknown { type_byte, .. } => *type_byte
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            BlockContent::Header(it) => it.to_bytes(),
+            BlockContent::Comment(it) => it.to_bytes(),
+            BlockContent::Data(it) => it.to_bytes(),
+            BlockContent::Palette(it) => it.to_bytes(),
+            BlockContent::Checksum(it) => it.to_bytes(),
+            BlockContent::Unknown { bytes, .. } => bytes.clone()
+        }
+    }
 }
 
 pub struct Block {
@@ -14,33 +44,44 @@ pub struct Block {
     pub content: BlockContent
 }
 
-impl TryFrom<&[u8]> for Block {
-    type Error = anyhow::Error;
+impl Block {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let content_bytes = self.content.to_bytes();
+        let mut bytes = Vec::with_capacity(5 + content_bytes.len());
 
-    fn try_from(bytes: &[u8]) -> Result<Block> {
-        if bytes.len() <= 5 {
-            return Err(anyhow!("Unable to parse a block: not enough bytes to store type + length."));
+        bytes.push(self.content.type_byte());
+        bytes.extend_from_slice(&(content_bytes.len() as u32).to_be_bytes());
+        bytes.extend(content_bytes);
+
+        bytes
+    }
+
+    //a Z block is rejected if it decompresses to more than max_data_length bytes (try_from uses DEFAULT_MAX_DATA_LENGTH)
+    pub fn try_from_with_limit(bytes: &[u8], max_data_length: usize) -> Result<Block> {
+        if bytes.len() <= 5 { //not enough bytes to store type + length
+            return Err(MiniPngError::TruncatedBlock { offset: 0 });
         }
 
         let block_type = bytes[0];
         let block_length = u32::from_be_bytes(bytes[1..=4].try_into().unwrap()); //safe unwrap because we have 4 bytes
         
         if block_length == 0 {
-            return Err(anyhow!("Unable to parse a block: according to its metadata, its length is 0."));
+            return Err(MiniPngError::ZeroLengthBlock { offset: 1 });
         }
 
-        let content_bytes = bytes.get(5..5+block_length as usize).ok_or(anyhow!("Unable to parse a block: there is a mismatch between block length and the actual number of bytes."))?;
-        
-        if !block_type.is_ascii() {
-            return Err(anyhow!("Unable to parse a block: its type is not a valid ASCII character (so it cannot be H, C or D)"));
-        }
+        let content_bytes = bytes.get(5..5+block_length as usize).ok_or(MiniPngError::TruncatedBlock { offset: 0 })?;
 
+        //content parsers report offsets relative to the content, which starts after type + length
         let content = match block_type {
-            b'H' => BlockContent::Header(HeaderBlock::try_from(content_bytes)?),
-            b'C' => BlockContent::Comment(CommentBlock::try_from(content_bytes)?),
-            b'D' => BlockContent::Data(DataBlock::try_from(content_bytes)?),
-            b'P' => BlockContent::Palette(PaletteBlock::try_from(content_bytes)?),
-            _ => { return Err(anyhow!("Unable to parse a block: its type is not one of H, C or D.")); }
+            b'H' => BlockContent::Header(HeaderBlock::try_from(content_bytes).map_err(|err| err.shifted(5))?),
+            b'C' => BlockContent::Comment(CommentBlock::try_from(content_bytes).map_err(|err| err.shifted(5))?),
+            b'D' => BlockContent::Data(DataBlock::try_from(content_bytes).map_err(|err| err.shifted(5))?),
+            b'Z' => BlockContent::Data(DataBlock::try_from_compressed(content_bytes, max_data_length).map_err(|err| err.shifted(5))?),
+            b'P' => BlockContent::Palette(PaletteBlock::try_from(content_bytes).map_err(|err| err.shifted(5))?),
+            b'K' => BlockContent::Checksum(ChecksumBlock::try_from(content_bytes).map_err(|err| err.shifted(5))?),
+            //like in PNG, a lowercase type means that the block is ancillary, so it can be safely kept without being understood
+            type_byte if type_byte.is_ascii_lowercase() => BlockContent::Unknown { type_byte, bytes: content_bytes.to_vec() },
+            _ => { return Err(MiniPngError::UnknownBlockType { type_byte: block_type, offset: 0 }); }
         };
 
         Ok(Block {
@@ -48,4 +89,21 @@ impl TryFrom<&[u8]> for Block {
             content
         })
     }
-}
\ No newline at end of file
+}
+
+impl From<BlockContent> for Block {
+    fn from(content: BlockContent) -> Block {
+        Block {
+            block_length: content.to_bytes().len() as u32,
+            content
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for Block {
+    type Error = MiniPngError;
+
+    fn try_from(bytes: &[u8]) -> Result<Block> {
+        Block::try_from_with_limit(bytes, DEFAULT_MAX_DATA_LENGTH)
+    }
+}
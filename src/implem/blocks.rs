@@ -0,0 +1,116 @@
+use super::block::Block;
+use super::error::{MiniPngError, Result};
+
+pub const MAGIC: [u8; 8] = [b'M', b'i', b'n', b'i', b'-', b'P', b'N', b'G'];
+
+//a block whose type and length have been read, but whose content has not been decoded yet
+pub struct RawBlock<'a> {
+    offset: usize,
+    bytes: &'a [u8] //type + length + content
+}
+
+impl<'a> RawBlock<'a> {
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn type_byte(&self) -> u8 {
+        self.bytes[0]
+    }
+
+    pub fn block_length(&self) -> u32 {
+        self.content().len() as u32
+    }
+
+    pub fn content(&self) -> &'a [u8] {
+        &self.bytes[5..]
+    }
+
+    //the whole block as it appears in the file, including its type and length
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    //Z blocks cannot inflate beyond DEFAULT_MAX_DATA_LENGTH bytes, parse_with_limit takes another limit
+    pub fn parse(&self) -> Result<Block> {
+        Block::try_from(self.bytes).map_err(|err| err.shifted(self.offset))
+    }
+
+    pub fn parse_with_limit(&self, max_data_length: usize) -> Result<Block> {
+        Block::try_from_with_limit(self.bytes, max_data_length).map_err(|err| err.shifted(self.offset))
+    }
+}
+
+//lazily walks the blocks of a MiniPNG byte stream, without decoding nor validating the image
+pub struct Blocks<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    done: bool
+}
+
+impl<'a> Blocks<'a> {
+    pub fn new(bytes: &'a [u8]) -> Result<Blocks<'a>> {
+        let magic_bytes = bytes.get(0..MAGIC.len()).ok_or(MiniPngError::BadMagic { offset: 0 })?;
+        if magic_bytes != MAGIC {
+            return Err(MiniPngError::BadMagic { offset: 0 });
+        }
+
+        Ok(Blocks {
+            bytes,
+            offset: MAGIC.len(),
+            done: false
+        })
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    fn read_block_length(bytes: &[u8]) -> Result<usize> {
+        if bytes.len() <= 5 { //not enough bytes to store type + length
+            return Err(MiniPngError::TruncatedBlock { offset: 0 });
+        }
+
+        let block_length = u32::from_be_bytes(bytes[1..=4].try_into().unwrap()) as usize; //safe unwrap because we have 4 bytes
+
+        if block_length == 0 {
+            return Err(MiniPngError::ZeroLengthBlock { offset: 1 });
+        }
+
+        if bytes.len() < 5 + block_length {
+            return Err(MiniPngError::TruncatedBlock { offset: 0 });
+        }
+
+        Ok(block_length)
+    }
+}
+
+impl<'a> Iterator for Blocks<'a> {
+    type Item = Result<RawBlock<'a>>;
+
+    fn next(&mut self) -> Option<Result<RawBlock<'a>>> {
+        if self.done || self.offset == self.bytes.len() {
+            return None;
+        }
+
+        let remaining_bytes = &self.bytes[self.offset..];
+
+        //a framing error means we cannot know where the next block starts, so iteration stops there
+        let block_length = match Blocks::read_block_length(remaining_bytes) {
+            Ok(block_length) => block_length,
+            Err(err) => {
+                self.done = true;
+                return Some(Err(err.shifted(self.offset)));
+            }
+        };
+
+        let raw_block = RawBlock {
+            offset: self.offset,
+            bytes: &remaining_bytes[..5 + block_length] //safe slicing, checked by read_block_length
+        };
+
+        self.offset += 5 + block_length;
+
+        Some(Ok(raw_block))
+    }
+}
@@ -0,0 +1,221 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use super::error::{MiniPngError, Result};
+use super::header_block::HeaderBlock;
+use super::mini_png::MiniPNG;
+use super::palette_block::PaletteBlock;
+use super::pixel::{Pixel, PixelType};
+
+const FILE_HEADER_LENGTH: usize = 14; //BITMAPFILEHEADER
+const INFO_HEADER_LENGTH: usize = 40; //BITMAPINFOHEADER, the newer headers only append fields to it
+const BI_RGB: u32 = 0; //no compression
+
+//BlackAndWhite images are written with 1 bit per pixel, GrayLevels and Palette with 8 bits (gray levels use a gray ramp palette) and the other type with 24 bits
+pub fn to_bmp(mini_png: &MiniPNG, mut writer: impl Write) -> Result<()> {
+    let image_width = mini_png.get_image_width();
+    let image_height = mini_png.get_image_height();
+    let pixel_type = mini_png.get_pixel_type();
+
+    let (bits_per_pixel, color_table): (u16, Vec<(u8, u8, u8)>) = match pixel_type {
+        PixelType::BlackAndWhite => (1, vec![(0, 0, 0), (255, 255, 255)]),
+        PixelType::GrayLevels => (8, (0..=255).map(|value| (value, value, value)).collect()),
+        PixelType::Palette => (8, mini_png.get_palette().unwrap().entries()), //safe unwrap since palette images always have a palette
+        PixelType::TwentyFourBitsColors => (24, Vec::new())
+    };
+
+    //every row is padded to a multiple of 4 bytes
+    let row_length = (image_width as usize * bits_per_pixel as usize).div_ceil(32) * 4;
+    let pixel_data_offset = FILE_HEADER_LENGTH + INFO_HEADER_LENGTH + 4 * color_table.len();
+    let file_length = pixel_data_offset + row_length * image_height as usize;
+
+    let mut bytes = Vec::with_capacity(file_length);
+
+    bytes.extend_from_slice(b"BM");
+    bytes.extend_from_slice(&(file_length as u32).to_le_bytes());
+    bytes.extend_from_slice(&[0; 4]); //reserved
+    bytes.extend_from_slice(&(pixel_data_offset as u32).to_le_bytes());
+
+    bytes.extend_from_slice(&(INFO_HEADER_LENGTH as u32).to_le_bytes());
+    bytes.extend_from_slice(&(image_width as i32).to_le_bytes());
+    bytes.extend_from_slice(&(image_height as i32).to_le_bytes()); //positive, so rows are stored bottom-up
+    bytes.extend_from_slice(&1u16.to_le_bytes()); //planes
+    bytes.extend_from_slice(&bits_per_pixel.to_le_bytes());
+    bytes.extend_from_slice(&BI_RGB.to_le_bytes());
+    bytes.extend_from_slice(&((row_length * image_height as usize) as u32).to_le_bytes());
+    bytes.extend_from_slice(&[0; 8]); //horizontal and vertical resolutions
+    bytes.extend_from_slice(&(color_table.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&[0; 4]); //important colors
+
+    for (r, g, b) in color_table {
+        bytes.extend_from_slice(&[b, g, r, 0]);
+    }
+
+    for x in (0..image_height).rev() {
+        let mut row = Vec::with_capacity(row_length);
+
+        match pixel_type {
+            PixelType::BlackAndWhite => {
+                let pixels: Vec<Pixel> = (0..image_width).map(|y| mini_png.get_pixel_at(x, y).unwrap()) //safe unwrap since we cannot go out of bounds
+                                                         .collect();
+
+                row.extend(Pixel::pack_bits(&pixels, Pixel::White));
+            },
+            _ => {
+                for y in 0..image_width {
+                    match mini_png.get_pixel_at(x, y).unwrap() { //safe unwrap since we cannot go out of bounds
+                        Pixel::Black | Pixel::White => unreachable!(),
+                        Pixel::Gray(value) | Pixel::Palette(value) => row.push(value),
+                        Pixel::TwentyFourBitsColors(r, g, b) => row.extend_from_slice(&[b, g, r])
+                    }
+                }
+            }
+        }
+
+        row.resize(row_length, 0);
+        bytes.extend(row);
+    }
+
+    writer.write_all(&bytes)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+pub fn save_bmp(mini_png: &MiniPNG, file_path: &Path) -> Result<()> {
+    let file = File::create(file_path)?;
+
+    to_bmp(mini_png, BufWriter::new(file))
+}
+
+//indexed images whose color table is black and white (resp. a gray ramp) are decoded as BlackAndWhite (resp. GrayLevels) images
+pub fn from_bmp(mut reader: impl Read) -> Result<MiniPNG> {
+    let mut bytes = Vec::<u8>::new();
+    reader.read_to_end(&mut bytes)?;
+
+    if bytes.get(0..2) != Some(b"BM") {
+        return Err(MiniPngError::InvalidBmp { reason: "the signature is not BM", offset: 0 });
+    }
+
+    let read_u16 = |offset: usize| bytes.get(offset..offset + 2)
+                                        .map(|slice| u16::from_le_bytes(slice.try_into().unwrap())) //safe unwrap since there are 2 bytes
+                                        .ok_or(MiniPngError::InvalidBmp { reason: "the headers are truncated", offset: bytes.len() });
+    let read_u32 = |offset: usize| bytes.get(offset..offset + 4)
+                                        .map(|slice| u32::from_le_bytes(slice.try_into().unwrap())) //safe unwrap since there are 4 bytes
+                                        .ok_or(MiniPngError::InvalidBmp { reason: "the headers are truncated", offset: bytes.len() });
+
+    let pixel_data_offset = read_u32(10)? as usize;
+    let info_header_length = read_u32(14)? as usize;
+
+    if info_header_length < INFO_HEADER_LENGTH {
+        return Err(MiniPngError::UnsupportedBmpFeature(format!("{} bytes long info headers", info_header_length)));
+    }
+
+    let image_width = read_u32(18)? as i32;
+    let signed_image_height = read_u32(22)? as i32;
+    let bits_per_pixel = read_u16(28)?;
+    let compression = read_u32(30)?;
+    let colors_used = read_u32(46)? as usize;
+
+    if image_width <= 0 || signed_image_height == 0 {
+        return Err(MiniPngError::InvalidBmp { reason: "the width must be positive and the height different from 0", offset: 18 });
+    }
+
+    if compression != BI_RGB {
+        return Err(MiniPngError::UnsupportedBmpFeature(String::from("compression")));
+    }
+
+    if !matches!(bits_per_pixel, 1 | 4 | 8 | 24) {
+        return Err(MiniPngError::UnsupportedBmpFeature(format!("{} bits per pixel", bits_per_pixel)));
+    }
+
+    let image_width = image_width as u32;
+    let image_height = signed_image_height.unsigned_abs();
+    let top_down = signed_image_height < 0; //a negative height means that the first row is the top one
+
+    let color_table: Vec<(u8, u8, u8)> = if bits_per_pixel == 24 {
+        Vec::new()
+    } else {
+        let colors_count = if colors_used == 0 { 1 << bits_per_pixel } else { colors_used };
+        if colors_count > 1 << bits_per_pixel {
+            return Err(MiniPngError::InvalidBmp { reason: "the color table has too many entries", offset: 46 });
+        }
+
+        let color_table_offset = FILE_HEADER_LENGTH + info_header_length;
+        let color_table_bytes = bytes.get(color_table_offset..color_table_offset + 4 * colors_count)
+                                     .ok_or(MiniPngError::InvalidBmp { reason: "the color table is truncated", offset: color_table_offset })?;
+
+        color_table_bytes.chunks(4)
+                         .map(|chunk| (chunk[2], chunk[1], chunk[0])) //safe access since there are 4 bytes per entry
+                         .collect()
+    };
+
+    let pixel_type = match bits_per_pixel {
+        24 => PixelType::TwentyFourBitsColors,
+        1 if color_table == [(0, 0, 0), (255, 255, 255)] => PixelType::BlackAndWhite,
+        8 if color_table.len() == 256 && color_table.iter().enumerate().all(|(i, entry)| *entry == (i as u8, i as u8, i as u8)) => PixelType::GrayLevels,
+        _ => PixelType::Palette
+    };
+
+    let row_length = (image_width as usize * bits_per_pixel as usize).div_ceil(32) * 4;
+
+    //the whole pixel array must be present before the pixels are allocated
+    let pixel_data_end = row_length.checked_mul(image_height as usize)
+                                   .and_then(|pixel_data_length| pixel_data_length.checked_add(pixel_data_offset));
+    if pixel_data_end.is_none_or(|end| end > bytes.len()) {
+        return Err(MiniPngError::InvalidBmp { reason: "the pixel data is truncated", offset: bytes.len() });
+    }
+
+    let mut pixels = Vec::with_capacity(image_width as usize * image_height as usize);
+
+    for x in 0..image_height as usize {
+        let stored_row_index = if top_down { x } else { image_height as usize - 1 - x };
+        let row_offset = pixel_data_offset + stored_row_index * row_length;
+        let row = bytes.get(row_offset..row_offset + row_length)
+                       .ok_or(MiniPngError::InvalidBmp { reason: "the pixel data is truncated", offset: bytes.len() })?;
+
+        for y in 0..image_width as usize {
+            let pixel = match bits_per_pixel {
+                24 => Pixel::TwentyFourBitsColors(row[3 * y + 2], row[3 * y + 1], row[3 * y]),
+                _ => {
+                    //indexes narrower than a byte are packed from the most significant bit
+                    let bits_per_pixel = bits_per_pixel as usize;
+                    let bit_index = y * bits_per_pixel;
+                    let index = (row[bit_index / 8] >> (8 - bits_per_pixel - bit_index % 8)) & ((1u16 << bits_per_pixel) - 1) as u8;
+
+                    if index as usize >= color_table.len() {
+                        return Err(MiniPngError::PaletteIndexOutOfRange { index, offset: row_offset + bit_index / 8 });
+                    }
+
+                    match pixel_type {
+                        PixelType::BlackAndWhite => if index == 1 { Pixel::White } else { Pixel::Black },
+                        PixelType::GrayLevels => Pixel::Gray(index),
+                        _ => Pixel::Palette(index)
+                    }
+                }
+            };
+
+            pixels.push(pixel);
+        }
+    }
+
+    let palette_block = match pixel_type {
+        PixelType::Palette => {
+            let color_table_bytes: Vec<u8> = color_table.iter()
+                                                        .flat_map(|(r, g, b)| [*r, *g, *b])
+                                                        .collect();
+
+            Some(PaletteBlock::try_from(color_table_bytes.as_slice())?)
+        },
+        _ => None
+    };
+
+    Ok(MiniPNG::from_parts(HeaderBlock::new(image_width, image_height, pixel_type), Vec::new(), palette_block, pixels))
+}
+
+pub fn from_bmp_file(file_path: &Path) -> Result<MiniPNG> {
+    let file = File::open(file_path)?;
+
+    from_bmp(BufReader::new(file))
+}
@@ -0,0 +1,33 @@
+use super::error::{MiniPngError, Result};
+
+//the CRC-32 of the whole block (type + length + content) that precedes this one
+#[derive(Clone, Copy)]
+pub struct ChecksumBlock(u32);
+
+impl TryFrom<&[u8]> for ChecksumBlock {
+    type Error = MiniPngError;
+
+    fn try_from(bytes: &[u8]) -> Result<ChecksumBlock> {
+        let crc_bytes: [u8; 4] = bytes.try_into().map_err(|_| MiniPngError::InvalidChecksumLength { length: bytes.len(), offset: 0 })?;
+
+        Ok(ChecksumBlock(u32::from_be_bytes(crc_bytes)))
+    }
+}
+
+impl ChecksumBlock {
+    pub fn of(block_bytes: &[u8]) -> ChecksumBlock {
+        ChecksumBlock(crc32fast::hash(block_bytes))
+    }
+
+    pub fn get_crc(&self) -> u32 {
+        self.0
+    }
+
+    pub fn matches(&self, block_bytes: &[u8]) -> bool {
+        self.0 == crc32fast::hash(block_bytes)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_be_bytes().to_vec()
+    }
+}
@@ -1,15 +1,16 @@
-use anyhow::{anyhow, Result};
+use super::error::{MiniPngError, Result};
 
+#[derive(Clone)]
 pub struct CommentBlock(String);
 
 impl TryFrom<&[u8]> for CommentBlock {
-    type Error = anyhow::Error;
+    type Error = MiniPngError;
 
     fn try_from(bytes: &[u8]) -> Result<CommentBlock> {
-        let are_all_chars_representable = bytes.iter().all(|byte| *byte >= 32 && *byte <= 127);
-        
-        if !are_all_chars_representable {
-            return Err(anyhow!("Unable to parse a comment: the text is not valid ASCII."));
+        let first_unrepresentable_char = bytes.iter().position(|byte| !(32..=127).contains(byte));
+
+        if let Some(offset) = first_unrepresentable_char {
+            return Err(MiniPngError::NonAsciiComment { offset });
         }
         
         Ok(CommentBlock(String::from_utf8(bytes.to_vec()).unwrap())) //safe unwrap because all bytes are valid ASCII
@@ -17,7 +18,29 @@ impl TryFrom<&[u8]> for CommentBlock {
 }
 
 impl CommentBlock {
+    //same as from_lossy, but one '?' replaces a whole non-ASCII character instead of each of its bytes
+    pub fn from_text(text: &str) -> CommentBlock {
+        let text = text.chars()
+                       .map(|char| if (' '..='\x7f').contains(&char) { char } else { '?' })
+                       .collect();
+
+        CommentBlock(text)
+    }
+
+    //replaces every byte that cannot be represented with a '?'
+    pub fn from_lossy(bytes: &[u8]) -> CommentBlock {
+        let text = bytes.iter()
+                        .map(|byte| if (32..=127).contains(byte) { *byte as char } else { '?' })
+                        .collect();
+
+        CommentBlock(text)
+    }
+
     pub fn get_comment(&self) -> String {
         self.0.clone()
     }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.as_bytes().to_vec()
+    }
 }
\ No newline at end of file
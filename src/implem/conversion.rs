@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use super::dithering::{self, Dithering};
+use super::header_block::HeaderBlock;
+use super::mini_png::MiniPNG;
+use super::palette_block::PaletteBlock;
+use super::pixel::{Pixel, PixelType};
+use super::quantization::{self, Quantizer};
+
+#[derive(Clone, Copy, Debug)]
+pub struct ConversionOptions {
+    pub threshold: u8, //gray levels (or luminances) greater than or equal to it become white when binarising
+    pub max_palette_entries: usize, //between 1 and 256, the palette is quantized if the image has more colors
+    pub quantizer: Quantizer,
+    pub dithering: Dithering //used when converting to BlackAndWhite or Palette, the threshold is ignored unless it is None
+}
+
+impl Default for ConversionOptions {
+    fn default() -> ConversionOptions {
+        ConversionOptions {
+            threshold: 128,
+            max_palette_entries: 256,
+            quantizer: Quantizer::default(),
+            dithering: Dithering::default()
+        }
+    }
+}
+
+//ITU-R BT.601 weights, rounded to the nearest integer
+pub fn luminance((r, g, b): (u8, u8, u8)) -> u8 {
+    ((299 * r as u32 + 587 * g as u32 + 114 * b as u32 + 500) / 1000) as u8
+}
+
+impl MiniPNG {
+    //comments and ancillary blocks are kept, the palette is only kept (or generated) for palette images
+    pub fn convert(&self, pixel_type: PixelType, options: ConversionOptions) -> MiniPNG {
+        let image_width = self.get_image_width();
+        let image_height = self.get_image_height();
+        let header_block = HeaderBlock::new(image_width, image_height, pixel_type);
+
+        if pixel_type == self.get_pixel_type() {
+            return self.with_pixels(header_block, self.get_palette(), self.rows().concat());
+        }
+
+        let colors: Vec<(u8, u8, u8)> = self.rgb_rows().concat();
+
+        match pixel_type {
+            PixelType::BlackAndWhite if options.dithering == Dithering::None => {
+                let pixels = colors.iter()
+                                   .map(|color| if luminance(*color) >= options.threshold { Pixel::White } else { Pixel::Black })
+                                   .collect();
+
+                self.with_pixels(header_block, None, pixels)
+            },
+            PixelType::BlackAndWhite => {
+                let grays: Vec<(u8, u8, u8)> = colors.iter()
+                                                     .map(|color| luminance(*color))
+                                                     .map(|value| (value, value, value))
+                                                     .collect();
+
+                let pixels = dithering::dither(&grays, image_width as usize, &[(0, 0, 0), (255, 255, 255)], options.dithering)
+                                       .into_iter()
+                                       .map(|index| if index == 1 { Pixel::White } else { Pixel::Black })
+                                       .collect();
+
+                self.with_pixels(header_block, None, pixels)
+            },
+            PixelType::GrayLevels => {
+                let pixels = colors.iter()
+                                   .map(|color| Pixel::Gray(luminance(*color)))
+                                   .collect();
+
+                self.with_pixels(header_block, None, pixels)
+            },
+            PixelType::TwentyFourBitsColors => {
+                let pixels = colors.iter()
+                                   .map(|(r, g, b)| Pixel::TwentyFourBitsColors(*r, *g, *b))
+                                   .collect();
+
+                self.with_pixels(header_block, None, pixels)
+            },
+            PixelType::Palette => {
+                let palette = quantization::quantize(&colors, options.max_palette_entries.min(256), options.quantizer);
+                let pixels = match options.dithering {
+                    Dithering::None => map_to_palette(&colors, &palette),
+                    dithering => dithering::dither(&colors, image_width as usize, &palette, dithering).into_iter()
+                                                                                                     .map(Pixel::Palette)
+                                                                                                     .collect()
+                };
+
+                self.with_pixels(header_block, Some(PaletteBlock::new(palette)), pixels)
+            }
+        }
+    }
+
+    //the pixels of the image row by row, without the padding of black and white images
+    pub(crate) fn rows(&self) -> Vec<Vec<Pixel>> {
+        (0..self.get_image_height()).map(|x| (0..self.get_image_width()).map(|y| self.get_pixel_at(x, y).unwrap()) //safe unwrap since we cannot go out of bounds
+                                                                      .collect())
+                                    .collect()
+    }
+
+    //same as rows, but with every pixel resolved to its color
+    pub(crate) fn rgb_rows(&self) -> Vec<Vec<(u8, u8, u8)>> {
+        let palette_block = self.get_palette();
+
+        self.rows()
+            .into_iter()
+            .map(|row| row.into_iter()
+                          .map(|pixel| pixel.to_rgb(palette_block.as_ref()))
+                          .collect())
+            .collect()
+    }
+}
+
+//each color is replaced by the index of the closest palette entry (in the RGB space)
+pub(crate) fn map_to_palette(colors: &[(u8, u8, u8)], palette: &[(u8, u8, u8)]) -> Vec<Pixel> {
+    let mut cache = HashMap::<(u8, u8, u8), u8>::new();
+
+    colors.iter()
+          .map(|color| Pixel::Palette(*cache.entry(*color).or_insert_with(|| nearest_palette_index(*color, palette))))
+          .collect()
+}
+
+pub(crate) fn nearest_palette_index((r, g, b): (u8, u8, u8), palette: &[(u8, u8, u8)]) -> u8 {
+    let distance = |(pr, pg, pb): (u8, u8, u8)| {
+        let (dr, dg, db) = (r as i32 - pr as i32, g as i32 - pg as i32, b as i32 - pb as i32);
+        dr * dr + dg * dg + db * db
+    };
+
+    (0..palette.len()).min_by_key(|i| distance(palette[*i]))
+                      .unwrap() as u8 //safe unwrap since a palette is never empty
+}
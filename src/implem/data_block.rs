@@ -1,17 +1,105 @@
-use anyhow::Result;
+use std::io::{Read, Write};
 
-pub struct DataBlock(Vec<u8>);
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use super::error::{MiniPngError, Result};
+
+pub struct DataBlock {
+    bytes: Vec<u8>, //always decompressed
+    compressed_bytes: Option<Vec<u8>> //the zlib stream stored in the file, for Z blocks
+}
 
 impl TryFrom<&[u8]> for DataBlock {
-    type Error = anyhow::Error;
+    type Error = MiniPngError;
 
     fn try_from(bytes: &[u8]) -> Result<DataBlock> {
-        Ok(DataBlock(Vec::from(bytes)))
+        Ok(DataBlock::from(Vec::from(bytes)))
+    }
+}
+
+impl From<Vec<u8>> for DataBlock {
+    fn from(bytes: Vec<u8>) -> DataBlock {
+        DataBlock {
+            bytes,
+            compressed_bytes: None
+        }
     }
 }
 
 impl DataBlock {
+    //the stream is rejected as soon as it inflates beyond max_length bytes, so a small block cannot exhaust the memory
+    //like the other content parsers, the offset of the error is relative to the content (the caller rebases it)
+    pub fn try_from_compressed(compressed_bytes: &[u8], max_length: usize) -> Result<DataBlock> {
+        let (bytes, is_truncated) = DataBlock::inflate(compressed_bytes, max_length)?;
+
+        if is_truncated {
+            return Err(MiniPngError::DecompressedTooLarge { limit: max_length, offset: 0 });
+        }
+
+        Ok(DataBlock {
+            bytes,
+            compressed_bytes: Some(compressed_bytes.to_vec())
+        })
+    }
+
+    //keeps the first max_length decompressed bytes, the stream is compressed again since the stored one holds more data
+    pub(crate) fn try_from_truncated_compressed(compressed_bytes: &[u8], max_length: usize) -> Result<DataBlock> {
+        let (bytes, _) = DataBlock::inflate(compressed_bytes, max_length)?;
+
+        Ok(DataBlock::compress(bytes))
+    }
+
+    //returns at most max_length bytes, and whether the stream holds more
+    fn inflate(compressed_bytes: &[u8], max_length: usize) -> Result<(Vec<u8>, bool)> {
+        let mut bytes = Vec::new();
+        ZlibDecoder::new(compressed_bytes).take(max_length as u64 + 1)
+                                          .read_to_end(&mut bytes)
+                                          .map_err(|_| MiniPngError::InvalidCompressedData { offset: 0 })?;
+
+        let is_truncated = bytes.len() > max_length;
+        bytes.truncate(max_length);
+
+        Ok((bytes, is_truncated))
+    }
+
+    pub fn compress(bytes: Vec<u8>) -> DataBlock {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&bytes).unwrap(); //safe unwrap since writing to a Vec cannot fail
+
+        DataBlock {
+            compressed_bytes: Some(encoder.finish().unwrap()), //ditto
+            bytes
+        }
+    }
+
+    //the compressed bytes are expected to decompress to the given bytes, this is the caller's responsibility
+    pub(crate) fn from_compressed_parts(bytes: Vec<u8>, compressed_bytes: Vec<u8>) -> DataBlock {
+        DataBlock {
+            bytes,
+            compressed_bytes: Some(compressed_bytes)
+        }
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.compressed_bytes.is_some()
+    }
+
     pub fn get_bytes(&self) -> Vec<u8> {
-        self.0.clone()
+        self.bytes.clone()
     }
-}
\ No newline at end of file
+
+    //the content of the block as it is stored in the file
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.compressed_bytes.clone().unwrap_or_else(|| self.bytes.clone())
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
@@ -0,0 +1,46 @@
+use super::error::{MiniPngError, Result};
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Strictness {
+    #[default]
+    Strict, //any deviation from the format is an error
+    Lenient //recoverable deviations are skipped or repaired, and reported as diagnostics
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DecodeOptions {
+    pub strictness: Strictness,
+    pub preserve_layout: bool //whether the block sequence is recorded, so that re-encoding an unmodified image gives back the same bytes
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Recovery {
+    Repaired, //the faulty bytes have been replaced
+    Skipped, //the faulty block (or bytes) has been ignored
+    Padded, //the missing pixel data has been filled with zeroes
+    Truncated, //the extra pixel data has been dropped
+    Kept //the faulty block has been used as is
+}
+
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub error: MiniPngError,
+    pub recovery: Recovery
+}
+
+impl DecodeOptions {
+    pub fn lenient() -> DecodeOptions {
+        DecodeOptions { strictness: Strictness::Lenient, ..DecodeOptions::default() }
+    }
+
+    //in strict mode the error is returned as is, in lenient mode it is turned into a diagnostic
+    pub(crate) fn recover(&self, diagnostics: &mut Vec<Diagnostic>, error: MiniPngError, recovery: Recovery) -> Result<()> {
+        match self.strictness {
+            Strictness::Strict => Err(error),
+            Strictness::Lenient => {
+                diagnostics.push(Diagnostic { error, recovery });
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use super::conversion::nearest_palette_index;
+
+type Color = (u8, u8, u8);
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Dithering {
+    #[default]
+    None, //each color is replaced by the closest palette entry
+    FloydSteinberg,
+    Atkinson,
+    Bayer4x4,
+    Bayer8x8
+}
+
+//replaces each color (given row by row) by the index of a palette entry, spreading the quantization error according to the algorithm
+pub fn dither(colors: &[Color], image_width: usize, palette: &[Color], dithering: Dithering) -> Vec<u8> {
+    match dithering {
+        Dithering::None => colors.iter().map(|color| nearest_palette_index(*color, palette)).collect(),
+        //(dx, dy, weight) of the neighbours receiving a part of the error, and the total weight
+        Dithering::FloydSteinberg => error_diffusion(colors, image_width, palette, &[(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)], 16.0),
+        //only 3/4 of the error is spread, which keeps more contrast
+        Dithering::Atkinson => error_diffusion(colors, image_width, palette, &[(1, 0, 1.0), (2, 0, 1.0), (-1, 1, 1.0), (0, 1, 1.0), (1, 1, 1.0), (0, 2, 1.0)], 8.0),
+        Dithering::Bayer4x4 => ordered(colors, image_width, palette, 4),
+        Dithering::Bayer8x8 => ordered(colors, image_width, palette, 8)
+    }
+}
+
+fn to_color(channels: [f32; 3]) -> Color {
+    let channel = |value: f32| value.round().clamp(0.0, 255.0) as u8;
+
+    (channel(channels[0]), channel(channels[1]), channel(channels[2]))
+}
+
+fn error_diffusion(colors: &[Color], image_width: usize, palette: &[Color], neighbours: &[(isize, isize, f32)], total_weight: f32) -> Vec<u8> {
+    let mut buffer: Vec<[f32; 3]> = colors.iter()
+                                          .map(|(r, g, b)| [*r as f32, *g as f32, *b as f32])
+                                          .collect();
+    let image_height = colors.len() / image_width;
+    let mut indexes = Vec::with_capacity(colors.len());
+
+    for i in 0..colors.len() {
+        let (x, y) = ((i % image_width) as isize, (i / image_width) as isize);
+
+        let index = nearest_palette_index(to_color(buffer[i]), palette);
+        let (r, g, b) = palette[index as usize];
+        let error = [buffer[i][0] - r as f32, buffer[i][1] - g as f32, buffer[i][2] - b as f32];
+
+        for (dx, dy, weight) in neighbours {
+            let (neighbour_x, neighbour_y) = (x + dx, y + dy);
+
+            if neighbour_x >= 0 && neighbour_x < image_width as isize && neighbour_y < image_height as isize {
+                let neighbour = neighbour_y as usize * image_width + neighbour_x as usize;
+
+                for channel in 0..3 {
+                    buffer[neighbour][channel] += error[channel] * weight / total_weight;
+                }
+            }
+        }
+
+        indexes.push(index);
+    }
+
+    indexes
+}
+
+//the size must be a power of 2, the values go from 0 to size² - 1
+fn bayer_matrix(size: usize) -> Vec<Vec<usize>> {
+    if size == 1 {
+        return vec![vec![0]];
+    }
+
+    let half = bayer_matrix(size / 2);
+
+    (0..size).map(|y| (0..size).map(|x| {
+                                   let offset = match (x < size / 2, y < size / 2) {
+                                       (true, true) => 0,
+                                       (false, true) => 2,
+                                       (true, false) => 3,
+                                       (false, false) => 1
+                                   };
+
+                                   4 * half[y % (size / 2)][x % (size / 2)] + offset
+                               })
+                               .collect())
+             .collect()
+}
+
+fn ordered(colors: &[Color], image_width: usize, palette: &[Color], size: usize) -> Vec<u8> {
+    let matrix = bayer_matrix(size);
+    //the palette is considered as a color cube to estimate the distance between its colors
+    let spread = 255.0 / ((palette.len() as f32).cbrt() - 1.0).max(1.0);
+
+    colors.iter()
+          .enumerate()
+          .map(|(i, (r, g, b))| {
+              let (x, y) = (i % image_width, i / image_width);
+              let offset = ((matrix[y % size][x % size] as f32 + 0.5) / (size * size) as f32 - 0.5) * spread;
+
+              nearest_palette_index(to_color([*r as f32 + offset, *g as f32 + offset, *b as f32 + offset]), palette)
+          })
+          .collect()
+}
@@ -0,0 +1,12 @@
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Compression {
+    #[default]
+    None, //pixel data is stored in D blocks
+    Deflate //pixel data is zlib-compressed and stored in Z blocks
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EncodeOptions {
+    pub compression: Compression,
+    pub checksums: bool //whether every block is followed by a K block holding its CRC-32
+}
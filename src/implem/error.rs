@@ -0,0 +1,200 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+use super::pixel::{Pixel, PixelType};
+
+pub type Result<T> = std::result::Result<T, MiniPngError>;
+
+//every variant except the I/O, PNG, unsupported feature and image editing related ones carries the offset (in bytes, from the start of the file) where the problem has been detected
+#[derive(Debug)]
+pub enum MiniPngError {
+    Io(io::Error),
+    PngEncoding(png::EncodingError),
+    PngDecoding(png::DecodingError),
+    UnsupportedPngFeature(String),
+    UnsupportedBmpFeature(String),
+    InvalidDimensions { image_width: u32, image_height: u32 },
+    PixelCountMismatch { expected: usize, actual: usize },
+    PixelOutOfBounds { x: u32, y: u32 },
+    PixelTypeMismatch { pixel: Pixel, pixel_type: PixelType },
+    InvalidPaletteSize { count: usize },
+    UndefinedPaletteEntry { index: u8, count: usize },
+    UnexpectedPalette { pixel_type: PixelType },
+    EmptyComment,
+    BadMagic { offset: usize },
+    TruncatedBlock { offset: usize },
+    ZeroLengthBlock { offset: usize },
+    UnknownBlockType { type_byte: u8, offset: usize },
+    InvalidHeaderLength { length: usize, offset: usize },
+    ZeroDimension { offset: usize },
+    InvalidPixelType { value: u8, offset: usize },
+    NonAsciiComment { offset: usize },
+    InvalidPaletteLength { length: usize, offset: usize },
+    TooManyPaletteEntries { count: usize, offset: usize },
+    HeaderCount { count: usize, offset: usize },
+    MissingData { offset: usize },
+    SizeMismatch { expected: usize, actual: usize, offset: usize },
+    PaletteCount { count: usize, offset: usize },
+    MissingPalette { offset: usize },
+    PaletteIndexOutOfRange { index: u8, offset: usize },
+    InvalidNetpbm { reason: &'static str, offset: usize },
+    InvalidBmp { reason: &'static str, offset: usize },
+    InvalidCompressedData { offset: usize },
+    DecompressedTooLarge { limit: usize, offset: usize },
+    InvalidChecksumLength { length: usize, offset: usize },
+    ChecksumMismatch { type_byte: u8, offset: usize }
+}
+
+impl MiniPngError {
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            MiniPngError::Io(_) | MiniPngError::PngEncoding(_) | MiniPngError::PngDecoding(_) | MiniPngError::UnsupportedPngFeature(_) | MiniPngError::UnsupportedBmpFeature(_)
+            | MiniPngError::InvalidDimensions { .. }
+            | MiniPngError::PixelCountMismatch { .. }
+            | MiniPngError::PixelOutOfBounds { .. }
+            | MiniPngError::PixelTypeMismatch { .. }
+            | MiniPngError::InvalidPaletteSize { .. }
+            | MiniPngError::UndefinedPaletteEntry { .. }
+            | MiniPngError::UnexpectedPalette { .. }
+            | MiniPngError::EmptyComment => None,
+            MiniPngError::BadMagic { offset }
+            | MiniPngError::TruncatedBlock { offset }
+            | MiniPngError::ZeroLengthBlock { offset }
+            | MiniPngError::UnknownBlockType { offset, .. }
+            | MiniPngError::InvalidHeaderLength { offset, .. }
+            | MiniPngError::ZeroDimension { offset }
+            | MiniPngError::InvalidPixelType { offset, .. }
+            | MiniPngError::NonAsciiComment { offset }
+            | MiniPngError::InvalidPaletteLength { offset, .. }
+            | MiniPngError::TooManyPaletteEntries { offset, .. }
+            | MiniPngError::HeaderCount { offset, .. }
+            | MiniPngError::MissingData { offset }
+            | MiniPngError::SizeMismatch { offset, .. }
+            | MiniPngError::PaletteCount { offset, .. }
+            | MiniPngError::MissingPalette { offset }
+            | MiniPngError::PaletteIndexOutOfRange { offset, .. }
+            | MiniPngError::InvalidNetpbm { offset, .. }
+            | MiniPngError::InvalidBmp { offset, .. }
+            | MiniPngError::InvalidCompressedData { offset }
+            | MiniPngError::DecompressedTooLarge { offset, .. }
+            | MiniPngError::InvalidChecksumLength { offset, .. }
+            | MiniPngError::ChecksumMismatch { offset, .. } => Some(*offset)
+        }
+    }
+
+    //block parsers only know offsets relative to the bytes they were given, the caller rebases them with this
+    pub(crate) fn shifted(mut self, by: usize) -> MiniPngError {
+        match &mut self {
+            MiniPngError::Io(_) | MiniPngError::PngEncoding(_) | MiniPngError::PngDecoding(_) | MiniPngError::UnsupportedPngFeature(_) | MiniPngError::UnsupportedBmpFeature(_)
+            | MiniPngError::InvalidDimensions { .. }
+            | MiniPngError::PixelCountMismatch { .. }
+            | MiniPngError::PixelOutOfBounds { .. }
+            | MiniPngError::PixelTypeMismatch { .. }
+            | MiniPngError::InvalidPaletteSize { .. }
+            | MiniPngError::UndefinedPaletteEntry { .. }
+            | MiniPngError::UnexpectedPalette { .. }
+            | MiniPngError::EmptyComment => {},
+            MiniPngError::BadMagic { offset }
+            | MiniPngError::TruncatedBlock { offset }
+            | MiniPngError::ZeroLengthBlock { offset }
+            | MiniPngError::UnknownBlockType { offset, .. }
+            | MiniPngError::InvalidHeaderLength { offset, .. }
+            | MiniPngError::ZeroDimension { offset }
+            | MiniPngError::InvalidPixelType { offset, .. }
+            | MiniPngError::NonAsciiComment { offset }
+            | MiniPngError::InvalidPaletteLength { offset, .. }
+            | MiniPngError::TooManyPaletteEntries { offset, .. }
+            | MiniPngError::HeaderCount { offset, .. }
+            | MiniPngError::MissingData { offset }
+            | MiniPngError::SizeMismatch { offset, .. }
+            | MiniPngError::PaletteCount { offset, .. }
+            | MiniPngError::MissingPalette { offset }
+            | MiniPngError::PaletteIndexOutOfRange { offset, .. }
+            | MiniPngError::InvalidNetpbm { offset, .. }
+            | MiniPngError::InvalidBmp { offset, .. }
+            | MiniPngError::InvalidCompressedData { offset }
+            | MiniPngError::DecompressedTooLarge { offset, .. }
+            | MiniPngError::InvalidChecksumLength { offset, .. }
+            | MiniPngError::ChecksumMismatch { offset, .. } => *offset += by
+        }
+
+        self
+    }
+}
+
+impl fmt::Display for MiniPngError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MiniPngError::Io(error) => write!(f, "I/O error: {}", error)?,
+            MiniPngError::PngEncoding(error) => write!(f, "Unable to encode the PNG file: {}", error)?,
+            MiniPngError::PngDecoding(error) => write!(f, "Unable to decode the PNG file: {}", error)?,
+            MiniPngError::UnsupportedPngFeature(feature) => write!(f, "Unable to convert the PNG file: MiniPNG does not support {}", feature)?,
+            MiniPngError::UnsupportedBmpFeature(feature) => write!(f, "Unable to convert the BMP file: {} are not supported", feature)?,
+            MiniPngError::InvalidDimensions { image_width, image_height } => write!(f, "Unable to create the image: its dimensions ({}x{}) must be greater than 0", image_width, image_height)?,
+            MiniPngError::PixelCountMismatch { expected, actual } => write!(f, "Unable to create the image: {} pixels are expected, but {} were given", expected, actual)?,
+            MiniPngError::PixelOutOfBounds { x, y } => write!(f, "Unable to set a pixel: ({}, {}) is outside of the image", x, y)?,
+            MiniPngError::PixelTypeMismatch { pixel, pixel_type } => write!(f, "Unable to set a pixel: {:?} cannot be stored in an image of type {}", pixel, pixel_type)?,
+            MiniPngError::InvalidPaletteSize { count } => write!(f, "Unable to set the palette: it must have between 1 and 256 entries, but {} were given", count)?,
+            MiniPngError::UndefinedPaletteEntry { index, count } => write!(f, "Unable to use the palette: it has {} entries, so the pixel {} does not exist in it", count, index)?,
+            MiniPngError::UnexpectedPalette { pixel_type } => write!(f, "Unable to set the palette: images of type {} do not use one", pixel_type)?,
+            MiniPngError::EmptyComment => write!(f, "Unable to add the comment: it is empty, and blocks cannot have a length of 0")?,
+            MiniPngError::BadMagic { .. } => write!(f, "This file is not a valid MiniPNG file (magic mismatch)")?,
+            MiniPngError::TruncatedBlock { .. } => write!(f, "Unable to parse a block: there is a mismatch between block length and the actual number of bytes")?,
+            MiniPngError::ZeroLengthBlock { .. } => write!(f, "Unable to parse a block: according to its metadata, its length is 0")?,
+            MiniPngError::UnknownBlockType { type_byte, .. } => write!(f, "Unable to parse a block: its type ({:#04x}) is neither one of H, C, D, Z, P or K nor an ancillary (lowercase) one", type_byte)?,
+            MiniPngError::InvalidHeaderLength { length, .. } => write!(f, "Unable to parse a header block: 9 bytes are expected to store width + height + pixel type, but {} were found", length)?,
+            MiniPngError::ZeroDimension { .. } => write!(f, "Unable to parse a header block: one (or both) of the image's dimension is (are) 0")?,
+            MiniPngError::InvalidPixelType { value, .. } => write!(f, "Unable to parse a header block: {} is not a valid pixel format type", value)?,
+            MiniPngError::NonAsciiComment { .. } => write!(f, "Unable to parse a comment: the text is not valid ASCII")?,
+            MiniPngError::InvalidPaletteLength { length, .. } => write!(f, "Unable to parse a palette block: there should be 3n bytes, but {} is not a multiple of 3", length)?,
+            MiniPngError::TooManyPaletteEntries { count, .. } => write!(f, "Unable to parse a palette block: there cannot be more than 256 entries, but {} were found", count)?,
+            MiniPngError::HeaderCount { count, .. } => write!(f, "Unable to parse the file: 1 header is expected, but {} were found", count)?,
+            MiniPngError::MissingData { .. } => write!(f, "Unable to parse the file: no data block has been found")?,
+            MiniPngError::SizeMismatch { expected, actual, .. } => write!(f, "Error detected after parsing the file: {} bytes of pixel data are expected, but {} were found", expected, actual)?,
+            MiniPngError::PaletteCount { count, .. } => write!(f, "Unable to parse the file: there cannot be more than one palette block, but {} were found", count)?,
+            MiniPngError::MissingPalette { .. } => write!(f, "Unable to parse the file: this file requires a palette, but none were found")?,
+            MiniPngError::PaletteIndexOutOfRange { index, .. } => write!(f, "Error while trying to parse the pixels: the pixel {} does not exist in the palette", index)?,
+            MiniPngError::InvalidNetpbm { reason, .. } => write!(f, "Unable to parse the Netpbm file: {}", reason)?,
+            MiniPngError::InvalidBmp { reason, .. } => write!(f, "Unable to parse the BMP file: {}", reason)?,
+            MiniPngError::InvalidCompressedData { .. } => write!(f, "Unable to parse a compressed data block: its content is not a valid zlib stream")?,
+            MiniPngError::DecompressedTooLarge { limit, .. } => write!(f, "Unable to parse a compressed data block: it inflates to more than the {} bytes of pixel data left in the image", limit)?,
+            MiniPngError::InvalidChecksumLength { length, .. } => write!(f, "Unable to parse a checksum block: 4 bytes are expected to store a CRC-32, but {} were found", length)?,
+            MiniPngError::ChecksumMismatch { type_byte, .. } => write!(f, "Integrity check failed: the block of type {} is corrupted", type_byte.escape_ascii())?
+        }
+
+        match self.offset() {
+            Some(offset) => write!(f, " (at byte {}).", offset),
+            None => write!(f, ".")
+        }
+    }
+}
+
+impl error::Error for MiniPngError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            MiniPngError::Io(error) => Some(error),
+            MiniPngError::PngEncoding(error) => Some(error),
+            MiniPngError::PngDecoding(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for MiniPngError {
+    fn from(error: io::Error) -> MiniPngError {
+        MiniPngError::Io(error)
+    }
+}
+
+impl From<png::EncodingError> for MiniPngError {
+    fn from(error: png::EncodingError) -> MiniPngError {
+        MiniPngError::PngEncoding(error)
+    }
+}
+
+impl From<png::DecodingError> for MiniPngError {
+    fn from(error: png::DecodingError) -> MiniPngError {
+        MiniPngError::PngDecoding(error)
+    }
+}
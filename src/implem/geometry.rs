@@ -0,0 +1,75 @@
+use super::header_block::HeaderBlock;
+use super::mini_png::MiniPNG;
+use super::pixel::Pixel;
+
+//like in get_pixel_at, x designates a row and y a column
+impl MiniPNG {
+    //returns the region of the given width and height whose top left corner is at row x and column y, or None if it is empty or goes out of bounds
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Option<MiniPNG> {
+        if width == 0 || height == 0
+        || x.checked_add(height)? > self.get_image_height() || y.checked_add(width)? > self.get_image_width() {
+            return None;
+        }
+
+        let pixels = self.rows()[x as usize..(x + height) as usize].iter()
+                                                                   .flat_map(|row| row[y as usize..(y + width) as usize].to_vec())
+                                                                   .collect();
+
+        Some(self.with_geometry(width, height, pixels))
+    }
+
+    pub fn flip_horizontal(&self) -> MiniPNG {
+        let pixels = self.rows()
+                         .into_iter()
+                         .flat_map(|row| row.into_iter().rev())
+                         .collect();
+
+        self.with_geometry(self.get_image_width(), self.get_image_height(), pixels)
+    }
+
+    pub fn flip_vertical(&self) -> MiniPNG {
+        let pixels = self.rows()
+                         .into_iter()
+                         .rev()
+                         .flatten()
+                         .collect();
+
+        self.with_geometry(self.get_image_width(), self.get_image_height(), pixels)
+    }
+
+    //swaps rows and columns, the top left corner stays in place
+    pub fn transpose(&self) -> MiniPNG {
+        let rows = self.rows();
+        let pixels = (0..self.get_image_width() as usize).flat_map(|y| rows.iter().map(move |row| row[y]))
+                                                         .collect::<Vec<Pixel>>();
+
+        self.with_geometry(self.get_image_height(), self.get_image_width(), pixels)
+    }
+
+    //clockwise
+    pub fn rotate90(&self) -> MiniPNG {
+        self.transpose().flip_horizontal()
+    }
+
+    pub fn rotate180(&self) -> MiniPNG {
+        let pixels = self.rows()
+                         .into_iter()
+                         .rev()
+                         .flat_map(|row| row.into_iter().rev())
+                         .collect();
+
+        self.with_geometry(self.get_image_width(), self.get_image_height(), pixels)
+    }
+
+    //clockwise, i.e. 90 degrees counterclockwise
+    pub fn rotate270(&self) -> MiniPNG {
+        self.transpose().flip_vertical()
+    }
+
+    //the pixel type and the palette are unchanged, the bits of black and white images are packed again when writing
+    fn with_geometry(&self, width: u32, height: u32, pixels: Vec<Pixel>) -> MiniPNG {
+        let header_block = HeaderBlock::new(width, height, self.get_pixel_type());
+
+        self.with_pixels(header_block, self.get_palette(), pixels)
+    }
+}
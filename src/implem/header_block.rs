@@ -1,4 +1,4 @@
-use anyhow::{anyhow, Result};
+use super::error::{MiniPngError, Result};
 
 use super::pixel::PixelType;
 
@@ -10,6 +10,15 @@ pub struct HeaderBlock {
 }
 
 impl HeaderBlock {
+    //the dimensions must be greater than 0, this is the caller's responsibility
+    pub(crate) fn new(image_width: u32, image_height: u32, pixel_type: PixelType) -> HeaderBlock {
+        HeaderBlock {
+            image_width,
+            image_height,
+            pixel_type
+        }
+    }
+
     pub fn get_image_width(&self) -> u32 {
         self.image_width
     }
@@ -21,14 +30,24 @@ impl HeaderBlock {
     pub fn get_pixel_type(&self) -> PixelType {
         self.pixel_type
     }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(9);
+
+        bytes.extend_from_slice(&self.image_width.to_be_bytes());
+        bytes.extend_from_slice(&self.image_height.to_be_bytes());
+        bytes.push(self.pixel_type.into());
+
+        bytes
+    }
 }
 
 impl TryFrom<&[u8]> for HeaderBlock {
-    type Error = anyhow::Error;
+    type Error = MiniPngError;
 
     fn try_from(bytes: &[u8]) -> Result<HeaderBlock> {
         if bytes.len() != 9 {
-            return Err(anyhow!("Unable to parse a header block: not enough bytes to store width + height + pixel type."));
+            return Err(MiniPngError::InvalidHeaderLength { length: bytes.len(), offset: 0 });
         }
             
         //these will never fail because we know that bytes.len() == 9
@@ -40,17 +59,13 @@ impl TryFrom<&[u8]> for HeaderBlock {
         let image_height = u32::from_be_bytes(next_four_bytes);
 
         if image_width == 0 || image_height == 0 {
-            return Err(anyhow!("Unable to parse a header block: one (or both) of the image's dimension is (are) 0."));
-        }
-
-        if last_byte > 3 {
-            return Err(anyhow!("Unable to parse a header block: {} is not a valid pixel format type.", last_byte));
+            return Err(MiniPngError::ZeroDimension { offset: if image_width == 0 { 0 } else { 4 } });
         }
 
         Ok(HeaderBlock {
             image_width,
             image_height,
-            pixel_type: last_byte.try_into().unwrap() //safe unwrap because we checked the value earlier
+            pixel_type: PixelType::try_from(last_byte).map_err(|err| err.shifted(8))?
         })
     }
 }
\ No newline at end of file
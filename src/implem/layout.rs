@@ -0,0 +1,15 @@
+//the sequence of blocks of a decoded file, used to re-encode it exactly as it was
+pub(crate) enum LayoutEntry {
+    Header(usize), //index among the header blocks of the file, only the first one is kept
+    Comment(usize), //index in MiniPNG::comment_blocks
+    Palette(usize), //index among the palette blocks of the file, only the first one is kept
+    Data { length: usize, compressed: Option<CompressedPayload> }, //length of the decompressed data
+    Checksum, //computed again from the preceding block when writing
+    Unknown(usize) //index in MiniPNG::unknown_blocks
+}
+
+//deflate streams are not unique, so the original one is reused as long as the data it compresses is unchanged
+pub(crate) struct CompressedPayload {
+    pub data_crc: u32,
+    pub bytes: Vec<u8>
+}
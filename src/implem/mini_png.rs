@@ -1,13 +1,17 @@
-use std::io::Read;
+use std::io::{Read, Write};
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, BufWriter};
 use std::path::Path;
 
-use anyhow::{anyhow, Result};
-
-use super::block::{Block, BlockContent};
+use super::block::{Block, BlockContent, MAX_BLOCK_LENGTH};
+use super::blocks::{Blocks, MAGIC};
+use super::checksum_block::ChecksumBlock;
 use super::data_block::DataBlock;
+use super::decode_options::{DecodeOptions, Diagnostic, Recovery};
+use super::encode_options::{Compression, EncodeOptions};
+use super::error::{MiniPngError, Result};
 use super::header_block::HeaderBlock;
+use super::layout::{CompressedPayload, LayoutEntry};
 use super::comment_block::CommentBlock;
 use super::palette_block::PaletteBlock;
 use super::pixel::{Pixel, PixelType};
@@ -16,87 +20,297 @@ pub struct MiniPNG {
     header_block: HeaderBlock,
     comment_blocks: Vec<CommentBlock>,
     palette_block: Option<PaletteBlock>,
+    unknown_blocks: Vec<(u8, Vec<u8>)>, //type + content of the ancillary blocks, re-emitted unchanged
+    layout: Option<Vec<LayoutEntry>>, //only recorded when decoding with DecodeOptions::preserve_layout
     pixels: Vec<Pixel>,
 }
 
 impl MiniPNG {
     pub fn from_file(file_path: &Path) -> Result<MiniPNG> {
+        MiniPNG::from_file_with_options(file_path, &DecodeOptions::default()).map(|(mini_png, _)| mini_png)
+    }
+
+    pub fn from_reader(reader: impl Read) -> Result<MiniPNG> {
+        MiniPNG::from_reader_with_options(reader, &DecodeOptions::default()).map(|(mini_png, _)| mini_png)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<MiniPNG> {
+        MiniPNG::from_bytes_with_options(bytes, &DecodeOptions::default()).map(|(mini_png, _)| mini_png)
+    }
+
+    pub fn from_file_with_options(file_path: &Path, options: &DecodeOptions) -> Result<(MiniPNG, Vec<Diagnostic>)> {
+        let file = File::open(file_path)?;
+
+        MiniPNG::from_reader_with_options(BufReader::new(file), options)
+    }
+
+    pub fn from_reader_with_options(mut reader: impl Read, options: &DecodeOptions) -> Result<(MiniPNG, Vec<Diagnostic>)> {
         let mut bytes = Vec::<u8>::new();
-        
-        {
-            let file = File::open(file_path)?;
-            let mut reader = BufReader::new(file);
-            
-            reader.read_to_end(&mut bytes)?;
-        }
-        
-        //check magic
-        let magic_bytes = bytes.as_slice().get(0..=7).ok_or(anyhow!("Unable to parse the file: there are not enough bytes to store the header."))?;
-        if magic_bytes != [b'M', b'i', b'n', b'i', b'-', b'P', b'N', b'G'] {
-            return Err(anyhow!("This file is not a valid MiniPNG file (magic mismatch)."));
-        }
-
-        let mut bytes = bytes.as_slice().get(8..).ok_or(anyhow!("Unable to parse the file: there is nothing after the magic number."))?;
-        
-        //parse blocks
-        let mut header_blocks = Vec::<HeaderBlock>::new();
+        reader.read_to_end(&mut bytes)?;
+
+        MiniPNG::from_bytes_with_options(&bytes, options)
+    }
+
+    //in strict mode, the returned diagnostics are always empty
+    pub fn from_bytes_with_options(bytes: &[u8], options: &DecodeOptions) -> Result<(MiniPNG, Vec<Diagnostic>)> {
+        let file_length = bytes.len();
+        let mut diagnostics = Vec::<Diagnostic>::new();
+
+        //parse blocks, remembering where the ones involved in the consistency checks start
+        let mut header_blocks = Vec::<(usize, HeaderBlock)>::new();
         let mut comment_blocks = Vec::<CommentBlock>::new();
-        let mut data_blocks = Vec::<DataBlock>::new();
-        let mut palette_blocks = Vec::<PaletteBlock>::new();
+        let mut data_blocks = Vec::<(usize, DataBlock)>::new();
+        let mut palette_blocks = Vec::<(usize, PaletteBlock)>::new();
+        let mut unknown_blocks = Vec::<(u8, Vec<u8>)>::new();
+        let mut layout = Vec::<LayoutEntry>::new();
+
+        //the header is looked for first, so that compressed blocks cannot inflate beyond the size of the image
+        let max_data_length = Blocks::new(bytes)?.filter_map(|raw_block| raw_block.ok())
+                                                 .filter(|raw_block| raw_block.type_byte() == b'H')
+                                                 .find_map(|raw_block| HeaderBlock::try_from(raw_block.content()).ok())
+                                                 .map(|header_block| MiniPNG::expected_data_size(header_block.get_image_width(), header_block.get_image_height(), header_block.get_pixel_type()))
+                                                 .unwrap_or(0);
+        let mut data_length = 0;
+
+        let mut raw_blocks = Blocks::new(bytes)?.peekable();
+        while let Some(raw_block) = raw_blocks.next() {
+            let raw_block = match raw_block {
+                Ok(raw_block) => raw_block,
+                Err(error) => { //the remaining bytes cannot be split into blocks
+                    options.recover(&mut diagnostics, error, Recovery::Skipped)?;
+                    break;
+                }
+            };
+            let offset = raw_block.offset();
 
-        loop {
-            let (block, next_bytes) = MiniPNG::try_parse_block(bytes)?;
+            //a block is checked against the checksum block following it (if any) before being parsed
+            if let Some(Ok(next_raw_block)) = raw_blocks.peek() {
+                let checksum = (next_raw_block.type_byte() == b'K').then(|| ChecksumBlock::try_from(next_raw_block.content()));
 
-            match block.content {
-                BlockContent::Comment(it) => comment_blocks.push(it),
-                BlockContent::Data(it) => data_blocks.push(it),
-                BlockContent::Header(it) => header_blocks.push(it),
-                BlockContent::Palette(it) => palette_blocks.push(it),
+                if let Some(Ok(checksum)) = checksum {
+                    if !checksum.matches(raw_block.bytes()) {
+                        options.recover(&mut diagnostics, MiniPngError::ChecksumMismatch { type_byte: raw_block.type_byte(), offset }, Recovery::Kept)?;
+                    }
+                }
             }
 
-            if next_bytes.len() == 0 {
-                break;
+            let block = match raw_block.parse_with_limit(max_data_length.saturating_sub(data_length)) {
+                Ok(block) => block,
+                Err(error @ MiniPngError::NonAsciiComment { .. }) => {
+                    options.recover(&mut diagnostics, error, Recovery::Repaired)?;
+                    Block::from(BlockContent::Comment(CommentBlock::from_lossy(raw_block.content())))
+                },
+                //like extra bytes in D blocks, the data beyond the size of the image is dropped
+                Err(error @ MiniPngError::DecompressedTooLarge { limit, .. }) => {
+                    options.recover(&mut diagnostics, error, Recovery::Truncated)?;
+                    let data_block = DataBlock::try_from_truncated_compressed(raw_block.content(), limit).unwrap(); //safe unwrap since the same bytes have just been inflated successfully
+                    Block::from(BlockContent::Data(data_block))
+                },
+                Err(error) => {
+                    options.recover(&mut diagnostics, error, Recovery::Skipped)?;
+                    continue;
+                }
+            };
+
+            if options.preserve_layout {
+                layout.push(MiniPNG::layout_entry(&block.content, header_blocks.len(), comment_blocks.len(), palette_blocks.len(), unknown_blocks.len()));
             }
 
-            bytes = next_bytes;
+            match block.content {
+                BlockContent::Comment(it) => comment_blocks.push(it),
+                BlockContent::Data(it) => {
+                    data_length += it.len();
+                    data_blocks.push((offset, it));
+                },
+                BlockContent::Header(it) => header_blocks.push((offset, it)),
+                BlockContent::Palette(it) => palette_blocks.push((offset, it)),
+                BlockContent::Checksum(_) => {}, //already verified with the block it follows
+                BlockContent::Unknown { type_byte, bytes } => unknown_blocks.push((type_byte, bytes))
+            }
         }
 
         let headers_count = header_blocks.len();
-        if headers_count != 1 {
-            return Err(anyhow!("Unable to parse the file: 1 header is expected, but {} were found.", headers_count));
+        if headers_count == 0 {
+            return Err(MiniPngError::HeaderCount { count: headers_count, offset: file_length });
+        }
+
+        //only the first header and palette are kept when recovering from duplicates
+        if headers_count > 1 {
+            options.recover(&mut diagnostics, MiniPngError::HeaderCount { count: headers_count, offset: header_blocks[1].0 }, Recovery::Skipped)?;
         }
 
-        let header_block = header_blocks[0]; //safe access since we checked the size earlier
+        let header_block = header_blocks[0].1; //safe access since we checked the size earlier
 
-        if data_blocks.len() == 0{
-            return Err(anyhow!("Unable to parse the file: no data block has been found."))
+        if palette_blocks.len() > 1 {
+            options.recover(&mut diagnostics, MiniPngError::PaletteCount { count: palette_blocks.len(), offset: palette_blocks[1].0 }, Recovery::Skipped)?;
+            palette_blocks.truncate(1);
         }
 
-        
+        if data_blocks.is_empty() {
+            return Err(MiniPngError::MissingData { offset: file_length });
+        }
 
-        let data_bytes: Vec<u8> = data_blocks.iter()
-                                             .map(|data_block| data_block.get_bytes())
-                                             .flatten()
-                                             .collect();
+        let mut data_bytes: Vec<u8> = data_blocks.iter()
+                                                 .flat_map(|(_, data_block)| data_block.get_bytes())
+                                                 .collect();
 
         let pixel_type = header_block.get_pixel_type();
         //check that the number of pixels matches the specified dimensions of the image
-        if !MiniPNG::data_size_matches_image_size(header_block.get_image_width(), header_block.get_image_height(), data_bytes.len(), pixel_type) {
-            return Err(anyhow!("Error detected after parsing the file: the file size does not match the number of pixels parsed."));
+        let expected_data_size = MiniPNG::expected_data_size(header_block.get_image_width(), header_block.get_image_height(), pixel_type);
+        if expected_data_size != data_bytes.len() {
+            let error = MiniPngError::SizeMismatch { expected: expected_data_size, actual: data_bytes.len(), offset: data_blocks[0].0 };
+            let recovery = if expected_data_size > data_bytes.len() { Recovery::Padded } else { Recovery::Truncated };
+
+            options.recover(&mut diagnostics, error, recovery)?;
+            data_bytes.resize(expected_data_size, 0);
         }
 
         let pixels = MiniPNG::process_pixels(pixel_type, data_bytes);
 
         if pixel_type == PixelType::Palette {
-            MiniPNG::palette_consistency_checks(&palette_blocks, &pixels)?;
+            MiniPNG::palette_consistency_checks(&palette_blocks, &data_blocks, &pixels, file_length)?;
         }
 
-        Ok(MiniPNG {
+        let mini_png = MiniPNG {
             header_block,
             comment_blocks,
-            palette_block: if palette_blocks.len() == 1 { Some(palette_blocks[0].clone()) } else { None },
+            palette_block: if palette_blocks.len() == 1 { Some(palette_blocks[0].1.clone()) } else { None },
+            unknown_blocks,
+            layout: if options.preserve_layout { Some(layout) } else { None },
             pixels
-        })
+        };
+
+        Ok((mini_png, diagnostics))
+    }
+
+    //every pixel is black, or refers to the single (black) entry of a placeholder palette to be replaced with with_palette
+    pub fn new(image_width: u32, image_height: u32, pixel_type: PixelType) -> Result<MiniPNG> {
+        if image_width == 0 || image_height == 0 {
+            return Err(MiniPngError::InvalidDimensions { image_width, image_height });
+        }
+
+        let pixel = match pixel_type {
+            PixelType::BlackAndWhite => Pixel::Black,
+            PixelType::GrayLevels => Pixel::Gray(0),
+            PixelType::Palette => Pixel::Palette(0),
+            PixelType::TwentyFourBitsColors => Pixel::TwentyFourBitsColors(0, 0, 0)
+        };
+
+        MiniPNG::from_pixels(image_width, image_height, vec![pixel; image_width as usize * image_height as usize])
+    }
+
+    //the pixels are given row by row, their type is deduced from the first one
+    //palette images get a placeholder palette of black entries, up to the greatest index used, to be replaced with with_palette
+    pub fn from_pixels(image_width: u32, image_height: u32, pixels: Vec<Pixel>) -> Result<MiniPNG> {
+        if image_width == 0 || image_height == 0 {
+            return Err(MiniPngError::InvalidDimensions { image_width, image_height });
+        }
+
+        let expected = image_width as usize * image_height as usize;
+        if pixels.len() != expected {
+            return Err(MiniPngError::PixelCountMismatch { expected, actual: pixels.len() });
+        }
+
+        let pixel_type = pixels[0].pixel_type(); //safe access since there is at least one pixel
+        if let Some(pixel) = pixels.iter().find(|pixel| pixel.pixel_type() != pixel_type) {
+            return Err(MiniPngError::PixelTypeMismatch { pixel: *pixel, pixel_type });
+        }
+
+        let palette_block = (pixel_type == PixelType::Palette).then(|| {
+            let entries_count = pixels.iter()
+                                      .map(|pixel| if let Pixel::Palette(index) = pixel { *index as usize + 1 } else { 0 })
+                                      .max()
+                                      .unwrap_or(1);
+
+            PaletteBlock::new(vec![(0, 0, 0); entries_count])
+        });
+
+        Ok(MiniPNG::from_parts(HeaderBlock::new(image_width, image_height, pixel_type), Vec::new(), palette_block, pixels))
+    }
+
+    //replaces the palette of a palette image, every pixel must still refer to an existing entry
+    pub fn with_palette(mut self, entries: Vec<(u8, u8, u8)>) -> Result<MiniPNG> {
+        let pixel_type = self.get_pixel_type();
+        if pixel_type != PixelType::Palette {
+            return Err(MiniPngError::UnexpectedPalette { pixel_type });
+        }
+
+        if entries.is_empty() || entries.len() > 256 {
+            return Err(MiniPngError::InvalidPaletteSize { count: entries.len() });
+        }
+
+        for pixel in &self.pixels {
+            if let Pixel::Palette(index) = pixel {
+                if *index as usize >= entries.len() {
+                    return Err(MiniPngError::UndefinedPaletteEntry { index: *index, count: entries.len() });
+                }
+            }
+        }
+
+        self.palette_block = Some(PaletteBlock::new(entries));
+
+        Ok(self)
+    }
+
+    //like in get_pixel_at, x designates a row and y a column
+    pub fn set_pixel_at(&mut self, x: u32, y: u32, pixel: Pixel) -> Result<()> {
+        let image_width = self.get_image_width();
+
+        if y >= image_width || x >= self.get_image_height() {
+            return Err(MiniPngError::PixelOutOfBounds { x, y });
+        }
+
+        let pixel_type = self.get_pixel_type();
+        if pixel.pixel_type() != pixel_type {
+            return Err(MiniPngError::PixelTypeMismatch { pixel, pixel_type });
+        }
+
+        if let Pixel::Palette(index) = pixel {
+            let entries_count = self.palette_block.as_ref().map(|palette| palette.entries().len()).unwrap_or(0);
+
+            if index as usize >= entries_count {
+                return Err(MiniPngError::UndefinedPaletteEntry { index, count: entries_count });
+            }
+        }
+
+        self.pixels[image_width as usize * x as usize + y as usize] = pixel;
+
+        Ok(())
+    }
+
+    //the comment must not be empty and only contain printable ASCII characters
+    pub fn add_comment(&mut self, comment: &str) -> Result<()> {
+        if comment.is_empty() {
+            return Err(MiniPngError::EmptyComment);
+        }
+
+        self.comment_blocks.push(CommentBlock::try_from(comment.as_bytes())?);
+
+        Ok(())
+    }
+
+    //the pixels are expected to match the header, this is the caller's responsibility
+    pub(crate) fn from_parts(header_block: HeaderBlock, comment_blocks: Vec<CommentBlock>, palette_block: Option<PaletteBlock>, pixels: Vec<Pixel>) -> MiniPNG {
+        MiniPNG {
+            header_block,
+            comment_blocks,
+            palette_block,
+            unknown_blocks: Vec::new(),
+            layout: None,
+            pixels
+        }
+    }
+
+    //builds an image sharing the comments and ancillary blocks of this one, the layout is dropped since the pixels changed
+    pub(crate) fn with_pixels(&self, header_block: HeaderBlock, palette_block: Option<PaletteBlock>, pixels: Vec<Pixel>) -> MiniPNG {
+        MiniPNG {
+            header_block,
+            comment_blocks: self.comment_blocks.clone(),
+            palette_block,
+            unknown_blocks: self.unknown_blocks.clone(),
+            layout: None,
+            pixels
+        }
     }
 
     pub fn get_image_width(&self) -> u32 {
@@ -125,47 +339,166 @@ impl MiniPNG {
             return None;
         }
 
-        Some(self.pixels[(image_width * x + y) as usize])
+        Some(self.pixels[image_width as usize * x as usize + y as usize])
     }
 
     pub fn get_rgb_at(&self, x: u32, y: u32) -> Option<(u8, u8, u8)> {
-        MiniPNG::get_pixel_at(&self, x, y).map(|pixel| {
-            match pixel {
-                Pixel::Black => (0, 0, 0),
-                Pixel::White => (255, 255, 255),
-                Pixel::Gray(value) => (value, value, value),
-                Pixel::TwentyFourBitsColors(r, g, b) => (r, g, b),
-                Pixel::Palette(value) => self.get_palette().unwrap().entries()[value as usize]
-            }
-        })
+        MiniPNG::get_pixel_at(self, x, y).map(|pixel| pixel.to_rgb(self.palette_block.as_ref()))
     }
 
     pub fn get_palette(&self) -> Option<PaletteBlock> {
         self.palette_block.clone()
     }
 
-    fn try_parse_block(bytes: &[u8]) -> Result<(Block, &[u8])> {
-        let block = Block::try_from(bytes)?;
+    pub fn get_unknown_blocks(&self) -> Vec<(u8, Vec<u8>)> {
+        self.unknown_blocks.clone()
+    }
 
-        let remaining_bytes = &bytes[5 + block.block_length as usize..]; //safe slicing
-        Ok((block, remaining_bytes))
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with_options(&EncodeOptions::default())
     }
 
-    fn palette_consistency_checks(palette_blocks: &Vec<PaletteBlock>, pixels: &Vec<Pixel>) -> Result<()> {
-        if palette_blocks.len() >= 2 {
-            return Err(anyhow!("Unable to parse the file: there cannot be more than one palette block, but {} were found.", palette_blocks.len()));
+    //the layout recorded while decoding, if it still matches the image, takes precedence over the options
+    pub fn to_bytes_with_options(&self, options: &EncodeOptions) -> Vec<u8> {
+        if let Some(bytes) = self.layout.as_ref().and_then(|layout| self.layout_to_bytes(layout)) {
+            return bytes;
+        }
+
+        let mut blocks = vec![Block::from(BlockContent::Header(self.header_block))];
+
+        for comment_block in &self.comment_blocks {
+            blocks.push(Block::from(BlockContent::Comment(comment_block.clone())));
+        }
+
+        for (type_byte, bytes) in &self.unknown_blocks {
+            blocks.push(Block::from(BlockContent::Unknown { type_byte: *type_byte, bytes: bytes.clone() }));
+        }
+
+        if let Some(palette_block) = &self.palette_block {
+            blocks.push(Block::from(BlockContent::Palette(palette_block.clone())));
+        }
+
+        let data_bytes = MiniPNG::pack_pixels(self.get_pixel_type(), &self.pixels);
+        for chunk in data_bytes.chunks(MAX_BLOCK_LENGTH) {
+            let data_block = match options.compression {
+                Compression::None => DataBlock::from(chunk.to_vec()),
+                Compression::Deflate => DataBlock::compress(chunk.to_vec())
+            };
+
+            blocks.push(Block::from(BlockContent::Data(data_block)));
         }
 
-        if palette_blocks.len() == 0 {
-            return Err(anyhow!("Unable to parse the file: this file requires a palette, but none were found."));
+        let mut bytes = Vec::from(MAGIC);
+        for block in blocks {
+            let block_bytes = block.to_bytes();
+            bytes.extend_from_slice(&block_bytes);
+
+            if options.checksums {
+                bytes.extend(Block::from(BlockContent::Checksum(ChecksumBlock::of(&block_bytes))).to_bytes());
+            }
         }
 
-        let palette_entries_count = palette_blocks[0].entries().len(); //safe
+        bytes
+    }
+
+    fn layout_entry(content: &BlockContent, headers_count: usize, comments_count: usize, palettes_count: usize, unknowns_count: usize) -> LayoutEntry {
+        match content {
+            BlockContent::Header(_) => LayoutEntry::Header(headers_count),
+            BlockContent::Comment(_) => LayoutEntry::Comment(comments_count),
+            BlockContent::Palette(_) => LayoutEntry::Palette(palettes_count),
+            BlockContent::Data(it) => LayoutEntry::Data {
+                length: it.len(),
+                compressed: it.is_compressed().then(|| CompressedPayload { data_crc: crc32fast::hash(&it.get_bytes()), bytes: it.to_bytes() })
+            },
+            BlockContent::Checksum(_) => LayoutEntry::Checksum,
+            BlockContent::Unknown { .. } => LayoutEntry::Unknown(unknowns_count)
+        }
+    }
+
+    //returns None if the image has been changed in a way the layout cannot describe anymore
+    fn layout_to_bytes(&self, layout: &[LayoutEntry]) -> Option<Vec<u8>> {
+        let data_bytes = MiniPNG::pack_pixels(self.get_pixel_type(), &self.pixels);
+
+        let count = |predicate: fn(&LayoutEntry) -> bool| layout.iter().filter(|entry| predicate(entry)).count();
+        let data_length: usize = layout.iter()
+                                       .map(|entry| if let LayoutEntry::Data { length, .. } = entry { *length } else { 0 })
+                                       .sum();
+
+        if count(|entry| matches!(entry, LayoutEntry::Comment(_))) != self.comment_blocks.len()
+            || count(|entry| matches!(entry, LayoutEntry::Unknown(_))) != self.unknown_blocks.len()
+            || count(|entry| matches!(entry, LayoutEntry::Palette(0))) != self.palette_block.iter().count()
+            || data_length != data_bytes.len() {
+            return None;
+        }
+
+        let mut bytes = Vec::from(MAGIC);
+        let mut previous_block_bytes = Vec::<u8>::new();
+        let mut data_offset = 0;
+
+        for entry in layout {
+            let content = match entry {
+                LayoutEntry::Header(0) => BlockContent::Header(self.header_block),
+                LayoutEntry::Comment(i) => BlockContent::Comment(self.comment_blocks[*i].clone()),
+                LayoutEntry::Palette(0) => BlockContent::Palette(self.palette_block.clone()?),
+                LayoutEntry::Data { length, compressed } => {
+                    let chunk = data_bytes[data_offset..data_offset + length].to_vec();
+                    data_offset += length;
+
+                    match compressed {
+                        None => BlockContent::Data(DataBlock::from(chunk)),
+                        Some(payload) if payload.data_crc == crc32fast::hash(&chunk) => BlockContent::Data(DataBlock::from_compressed_parts(chunk, payload.bytes.clone())),
+                        Some(_) => BlockContent::Data(DataBlock::compress(chunk))
+                    }
+                },
+                LayoutEntry::Checksum => BlockContent::Checksum(ChecksumBlock::of(&previous_block_bytes)),
+                LayoutEntry::Unknown(i) => {
+                    let (type_byte, bytes) = &self.unknown_blocks[*i];
+                    BlockContent::Unknown { type_byte: *type_byte, bytes: bytes.clone() }
+                },
+                LayoutEntry::Header(_) | LayoutEntry::Palette(_) => continue //duplicates dropped by a lenient decoding
+            };
+
+            previous_block_bytes = Block::from(content).to_bytes();
+            bytes.extend_from_slice(&previous_block_bytes);
+        }
+
+        Some(bytes)
+    }
+
+    pub fn write_to(&self, writer: impl Write) -> Result<()> {
+        self.write_to_with_options(writer, &EncodeOptions::default())
+    }
+
+    pub fn write_to_with_options(&self, mut writer: impl Write, options: &EncodeOptions) -> Result<()> {
+        writer.write_all(&self.to_bytes_with_options(options))?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    pub fn save(&self, file_path: &Path) -> Result<()> {
+        self.save_with_options(file_path, &EncodeOptions::default())
+    }
+
+    pub fn save_with_options(&self, file_path: &Path, options: &EncodeOptions) -> Result<()> {
+        let file = File::create(file_path)?;
+
+        self.write_to_with_options(BufWriter::new(file), options)
+    }
+
+    fn palette_consistency_checks(palette_blocks: &[(usize, PaletteBlock)], data_blocks: &[(usize, DataBlock)], pixels: &[Pixel], file_length: usize) -> Result<()> {
+        if palette_blocks.is_empty() {
+            return Err(MiniPngError::MissingPalette { offset: file_length });
+        }
+
+        let palette_entries_count = palette_blocks[0].1.entries().len(); //safe
 
         //ensure that all the pixels exist within the palette
-        for pixel in pixels {
+        for (i, pixel) in pixels.iter().enumerate() {
             match pixel {
-                Pixel::Palette(value) => if *value as usize >= palette_entries_count { return Err(anyhow!("Error while trying to parse the pixels: the pixel {} does not exist in the palette.", value)); }
+                Pixel::Palette(value) => if *value as usize >= palette_entries_count {
+                    return Err(MiniPngError::PaletteIndexOutOfRange { index: *value, offset: MiniPNG::data_byte_offset(data_blocks, i) });
+                }
                 _ => unreachable!()
             }
         }
@@ -173,11 +506,28 @@ impl MiniPNG {
         Ok(())
     }
 
-    fn data_size_matches_image_size(image_width: u32, image_height: u32, bytes_count: usize, pixel_type: PixelType) -> bool {
+    //finds where the i-th byte of the concatenated (decompressed) pixel data is located in the file
+    fn data_byte_offset(data_blocks: &[(usize, DataBlock)], mut i: usize) -> usize {
+        for (offset, data_block) in data_blocks {
+            let length = data_block.len();
+            if i < length {
+                //the byte cannot be located within a compressed block, so the block itself is pointed
+                return if data_block.is_compressed() { *offset } else { offset + 5 + i };
+            }
+
+            i -= length;
+        }
+
+        unreachable!()
+    }
+
+    fn expected_data_size(image_width: u32, image_height: u32, pixel_type: PixelType) -> usize {
+        let pixels_count = image_width as usize * image_height as usize;
+
         match pixel_type {
-            PixelType::BlackAndWhite => ((image_width * image_height) as f32 / 8f32).ceil() as usize == bytes_count,
-            PixelType::GrayLevels | PixelType::Palette => (image_width * image_height) as usize == bytes_count,
-            PixelType::TwentyFourBitsColors => (image_width * image_height * 3) as usize == bytes_count
+            PixelType::BlackAndWhite => pixels_count.div_ceil(8),
+            PixelType::GrayLevels | PixelType::Palette => pixels_count,
+            PixelType::TwentyFourBitsColors => pixels_count * 3
         }
     }
 
@@ -215,4 +565,19 @@ impl MiniPNG {
 
         result
     }
+
+    fn pack_pixels(pixel_type: PixelType, pixels: &[Pixel]) -> Vec<u8> {
+        match pixel_type {
+            PixelType::BlackAndWhite => Pixel::pack_bits(pixels, Pixel::White), //the last byte is padded with black pixels if needed
+            PixelType::GrayLevels | PixelType::Palette | PixelType::TwentyFourBitsColors => {
+                pixels.iter()
+                      .flat_map(|pixel| match pixel {
+                          Pixel::Black | Pixel::White => unreachable!(),
+                          Pixel::Gray(value) | Pixel::Palette(value) => vec![*value],
+                          Pixel::TwentyFourBitsColors(r, g, b) => vec![*r, *g, *b]
+                      })
+                      .collect()
+            }
+        }
+    }
 }
\ No newline at end of file
@@ -1,7 +1,22 @@
 pub mod block;
+pub mod blocks;
+pub mod bmp;
+pub mod checksum_block;
 pub mod comment_block;
+pub mod conversion;
 pub mod data_block;
+pub mod decode_options;
+pub mod dithering;
+pub mod encode_options;
+pub mod error;
+pub mod geometry;
 pub mod header_block;
+mod layout;
 pub mod mini_png;
+pub mod netpbm;
 pub mod palette_block;
-pub mod pixel;
\ No newline at end of file
+pub mod pixel;
+pub mod png_codec;
+pub mod quantization;
+pub mod render;
+pub mod resampling;
\ No newline at end of file
@@ -0,0 +1,295 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use super::comment_block::CommentBlock;
+use super::error::{MiniPngError, Result};
+use super::header_block::HeaderBlock;
+use super::mini_png::MiniPNG;
+use super::pixel::{Pixel, PixelType};
+
+const MAX_ASCII_LINE_LENGTH: usize = 70; //as recommended by the Netpbm specification
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum NetpbmEncoding {
+    Ascii, //P1, P2 and P3
+    Binary //P4, P5 and P6
+}
+
+//BlackAndWhite images are written as PBM, GrayLevels as PGM, and the other types as PPM (palettes are expanded)
+pub fn to_netpbm(mini_png: &MiniPNG, mut writer: impl Write, encoding: NetpbmEncoding) -> Result<()> {
+    let pixel_type = mini_png.get_pixel_type();
+    let magic_number = match (pixel_type, encoding) {
+        (PixelType::BlackAndWhite, NetpbmEncoding::Ascii) => "P1",
+        (PixelType::GrayLevels, NetpbmEncoding::Ascii) => "P2",
+        (_, NetpbmEncoding::Ascii) => "P3",
+        (PixelType::BlackAndWhite, NetpbmEncoding::Binary) => "P4",
+        (PixelType::GrayLevels, NetpbmEncoding::Binary) => "P5",
+        (_, NetpbmEncoding::Binary) => "P6"
+    };
+
+    let mut header = format!("{}\n", magic_number);
+    for comment in mini_png.get_comments() {
+        header.push_str(&format!("# {}\n", comment));
+    }
+
+    header.push_str(&format!("{} {}\n", mini_png.get_image_width(), mini_png.get_image_height()));
+    if pixel_type != PixelType::BlackAndWhite {
+        header.push_str("255\n");
+    }
+
+    writer.write_all(header.as_bytes())?;
+
+    let mut samples = Vec::<u8>::new(); //one byte per sample, except for P4 where they are packed
+
+    for x in 0..mini_png.get_image_height() {
+        let row: Vec<Pixel> = (0..mini_png.get_image_width()).map(|y| mini_png.get_pixel_at(x, y).unwrap()) //safe unwrap since we cannot go out of bounds
+                                                              .collect();
+
+        match (pixel_type, encoding) {
+            //in PBM, 1 means black
+            (PixelType::BlackAndWhite, NetpbmEncoding::Ascii) => samples.extend(row.iter().map(|pixel| (*pixel == Pixel::Black) as u8)),
+            (PixelType::BlackAndWhite, NetpbmEncoding::Binary) => samples.extend(Pixel::pack_bits(&row, Pixel::Black)),
+            (PixelType::GrayLevels, _) => {
+                for pixel in row {
+                    match pixel {
+                        Pixel::Gray(value) => samples.push(value),
+                        _ => unreachable!()
+                    }
+                }
+            },
+            (_, _) => {
+                for y in 0..mini_png.get_image_width() {
+                    let (r, g, b) = mini_png.get_rgb_at(x, y).unwrap(); //safe unwrap since we cannot go out of bounds
+                    samples.extend_from_slice(&[r, g, b]);
+                }
+            }
+        }
+    }
+
+    match encoding {
+        NetpbmEncoding::Binary => writer.write_all(&samples)?,
+        NetpbmEncoding::Ascii => {
+            //P1 samples are single digits that do not need to be separated
+            let separator = if pixel_type == PixelType::BlackAndWhite { "" } else { " " };
+            let mut line = String::new();
+
+            for sample in samples {
+                let token = sample.to_string();
+
+                if !line.is_empty() && line.len() + separator.len() + token.len() > MAX_ASCII_LINE_LENGTH {
+                    writeln!(writer, "{}", line)?;
+                    line.clear();
+                }
+
+                if !line.is_empty() {
+                    line.push_str(separator);
+                }
+
+                line.push_str(&token);
+            }
+
+            if !line.is_empty() {
+                writeln!(writer, "{}", line)?;
+            }
+        }
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+pub fn save_netpbm(mini_png: &MiniPNG, file_path: &Path, encoding: NetpbmEncoding) -> Result<()> {
+    let file = File::create(file_path)?;
+
+    to_netpbm(mini_png, BufWriter::new(file), encoding)
+}
+
+//only the first image of the file is read, the header comments become MiniPNG comments
+pub fn from_netpbm(mut reader: impl Read) -> Result<MiniPNG> {
+    let mut bytes = Vec::<u8>::new();
+    reader.read_to_end(&mut bytes)?;
+
+    let mut parser = NetpbmParser {
+        bytes: &bytes,
+        position: 0,
+        comments: Vec::new()
+    };
+
+    let magic_number = match bytes.get(0..2) {
+        Some([b'P', digit @ b'1'..=b'6']) => digit - b'0',
+        _ => return Err(MiniPngError::InvalidNetpbm { reason: "the magic number is not one of P1 to P6", offset: 0 })
+    };
+    parser.position = 2;
+
+    let image_width = parser.next_number()?;
+    let image_height = parser.next_number()?;
+
+    if image_width == 0 || image_height == 0 {
+        return Err(MiniPngError::InvalidNetpbm { reason: "the dimensions must be greater than 0", offset: parser.position });
+    }
+
+    let max_value = match magic_number {
+        1 | 4 => 1,
+        _ => parser.next_number()?
+    };
+
+    if max_value == 0 || max_value > u16::MAX as u32 {
+        return Err(MiniPngError::InvalidNetpbm { reason: "the maximum sample value must be between 1 and 65535", offset: parser.position });
+    }
+
+    let binary = magic_number >= 4;
+    if binary {
+        parser.position += 1; //a single whitespace separates the header from the raster
+    }
+
+    let pixel_type = match magic_number {
+        1 | 4 => PixelType::BlackAndWhite,
+        2 | 5 => PixelType::GrayLevels,
+        _ => PixelType::TwentyFourBitsColors
+    };
+
+    let pixels_count = (image_width as usize).checked_mul(image_height as usize)
+                                             .ok_or(MiniPngError::InvalidNetpbm { reason: "the image is too big", offset: parser.position })?;
+
+    //every sample takes at least one byte (one character in ASCII), except in P4 where they are packed, so a truncated raster is detected before allocating the pixels
+    let bytes_per_sample = if binary && max_value > 255 { 2 } else { 1 };
+    let channels = if pixel_type == PixelType::TwentyFourBitsColors { 3 } else { 1 };
+    let minimum_raster_length = match magic_number {
+        4 => (image_width as usize).div_ceil(8).checked_mul(image_height as usize),
+        _ => pixels_count.checked_mul(channels * bytes_per_sample)
+    };
+
+    if minimum_raster_length.is_none_or(|length| length > bytes.len().saturating_sub(parser.position)) {
+        return Err(MiniPngError::InvalidNetpbm { reason: "the raster is truncated", offset: bytes.len() });
+    }
+
+    //samples are rescaled to 8 bits
+    let mut pixels = Vec::with_capacity(pixels_count);
+    let next_sample = |parser: &mut NetpbmParser| -> Result<u8> {
+        let sample = match (binary, max_value) {
+            (false, _) => parser.next_number()?,
+            (true, 0..=255) => parser.next_byte()? as u32,
+            (true, _) => u16::from_be_bytes([parser.next_byte()?, parser.next_byte()?]) as u32
+        };
+
+        if sample > max_value {
+            return Err(MiniPngError::InvalidNetpbm { reason: "a sample is greater than the maximum sample value", offset: parser.position });
+        }
+
+        Ok(((sample * 255 + max_value / 2) / max_value) as u8)
+    };
+
+    match magic_number {
+        1 => {
+            for _ in 0..pixels_count {
+                pixels.push(if parser.next_bit()? { Pixel::Black } else { Pixel::White });
+            }
+        },
+        4 => {
+            //every row is padded to a byte boundary
+            for _ in 0..image_height {
+                let row_bytes = parser.next_bytes((image_width as usize).div_ceil(8))?;
+
+                for y in 0..image_width as usize {
+                    pixels.push(if (row_bytes[y / 8] >> (7 - y % 8)) & 1 == 1 { Pixel::Black } else { Pixel::White });
+                }
+            }
+        },
+        2 | 5 => {
+            for _ in 0..pixels_count {
+                pixels.push(Pixel::Gray(next_sample(&mut parser)?));
+            }
+        },
+        _ => {
+            for _ in 0..pixels_count {
+                pixels.push(Pixel::TwentyFourBitsColors(next_sample(&mut parser)?, next_sample(&mut parser)?, next_sample(&mut parser)?));
+            }
+        }
+    }
+
+    let comment_blocks = parser.comments.iter()
+                                        .map(|comment| CommentBlock::from_text(comment))
+                                        .collect();
+
+    Ok(MiniPNG::from_parts(HeaderBlock::new(image_width, image_height, pixel_type), comment_blocks, None, pixels))
+}
+
+pub fn from_netpbm_file(file_path: &Path) -> Result<MiniPNG> {
+    let file = File::open(file_path)?;
+
+    from_netpbm(BufReader::new(file))
+}
+
+struct NetpbmParser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    comments: Vec<String>
+}
+
+impl<'a> NetpbmParser<'a> {
+    fn skip_whitespaces_and_comments(&mut self) {
+        while let Some(byte) = self.bytes.get(self.position) {
+            match byte {
+                b'#' => {
+                    let comment_length = self.bytes[self.position..].iter()
+                                                                    .position(|byte| *byte == b'\n' || *byte == b'\r')
+                                                                    .unwrap_or(self.bytes.len() - self.position);
+
+                    //empty comments are dropped since MiniPNG blocks cannot be empty
+                    let comment = String::from_utf8_lossy(&self.bytes[self.position + 1..self.position + comment_length]);
+                    if !comment.trim().is_empty() {
+                        self.comments.push(String::from(comment.trim()));
+                    }
+                    self.position += comment_length;
+                },
+                byte if byte.is_ascii_whitespace() => self.position += 1,
+                _ => break
+            }
+        }
+    }
+
+    fn next_number(&mut self) -> Result<u32> {
+        self.skip_whitespaces_and_comments();
+
+        let start = self.position;
+        while self.bytes.get(self.position).is_some_and(|byte| byte.is_ascii_digit()) {
+            self.position += 1;
+        }
+
+        if start == self.position {
+            return Err(MiniPngError::InvalidNetpbm { reason: "a number was expected", offset: start });
+        }
+
+        //safe unwrap since there are only digits, the parsing itself fails on overflow
+        std::str::from_utf8(&self.bytes[start..self.position]).unwrap()
+                                                              .parse()
+                                                              .map_err(|_| MiniPngError::InvalidNetpbm { reason: "the number is too big", offset: start })
+    }
+
+    //P1 samples do not need to be separated by whitespaces
+    fn next_bit(&mut self) -> Result<bool> {
+        self.skip_whitespaces_and_comments();
+
+        let bit = match self.bytes.get(self.position) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(MiniPngError::InvalidNetpbm { reason: "0 or 1 was expected", offset: self.position })
+        };
+
+        self.position += 1;
+        Ok(bit)
+    }
+
+    fn next_byte(&mut self) -> Result<u8> {
+        Ok(self.next_bytes(1)?[0])
+    }
+
+    fn next_bytes(&mut self, count: usize) -> Result<&'a [u8]> {
+        let bytes = self.bytes.get(self.position..self.position + count).ok_or(MiniPngError::InvalidNetpbm { reason: "the raster is truncated", offset: self.bytes.len() })?;
+
+        self.position += count;
+        Ok(bytes)
+    }
+}
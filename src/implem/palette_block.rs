@@ -1,4 +1,4 @@
-use anyhow::{anyhow, Result};
+use super::error::{MiniPngError, Result};
 
 type PaletteEntry = (u8, u8, u8);
 
@@ -8,15 +8,15 @@ pub struct PaletteBlock {
 }
 
 impl TryFrom<&[u8]> for PaletteBlock {
-    type Error = anyhow::Error;
+    type Error = MiniPngError;
 
     fn try_from(bytes: &[u8]) -> Result<PaletteBlock> {
-        if bytes.len() % 3 != 0 { //no need to check for 0 since it's done in Block::try_from
-            return Err(anyhow!("Unable to parse a palette block: there should be 3n bytes, but {} is not a multiple of 3.", bytes.len()));
+        if !bytes.len().is_multiple_of(3) { //no need to check for 0 since it's done in Block::try_from
+            return Err(MiniPngError::InvalidPaletteLength { length: bytes.len(), offset: 0 });
         }
 
         if bytes.len() > 256 * 3 {
-            return Err(anyhow!("Unable to parse a palette block: there cannot be more than 256 entries, but {} were found.", bytes.len() / 3));
+            return Err(MiniPngError::TooManyPaletteEntries { count: bytes.len() / 3, offset: 256 * 3 });
         }
 
         Ok(PaletteBlock {
@@ -28,7 +28,22 @@ impl TryFrom<&[u8]> for PaletteBlock {
 }
 
 impl PaletteBlock {
+    //there must be between 1 and 256 entries, this is the caller's responsibility
+    pub(crate) fn new(entries: Vec<PaletteEntry>) -> PaletteBlock {
+        PaletteBlock { entries }
+    }
+
     pub fn entries(&self) -> Vec<PaletteEntry> {
         self.entries.clone()
     }
+
+    pub fn get_entry(&self, index: u8) -> Option<PaletteEntry> {
+        self.entries.get(index as usize).copied()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.entries.iter()
+                    .flat_map(|(r, g, b)| [*r, *g, *b])
+                    .collect()
+    }
 }
@@ -1,6 +1,7 @@
 use std::fmt;
 
-use anyhow::{anyhow, Result};
+use super::error::{MiniPngError, Result};
+use super::palette_block::PaletteBlock;
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum Pixel {
@@ -11,7 +12,7 @@ pub enum Pixel {
     TwentyFourBitsColors(u8, u8, u8)
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum PixelType {
     BlackAndWhite,
     GrayLevels,
@@ -19,20 +20,64 @@ pub enum PixelType {
     TwentyFourBitsColors
 }
 
+impl Pixel {
+    //palette pixels must refer to an entry of the given palette
+    pub(crate) fn to_rgb(self, palette_block: Option<&PaletteBlock>) -> (u8, u8, u8) {
+        match self {
+            Pixel::Black => (0, 0, 0),
+            Pixel::White => (255, 255, 255),
+            Pixel::Gray(value) => (value, value, value),
+            Pixel::TwentyFourBitsColors(r, g, b) => (r, g, b),
+            Pixel::Palette(value) => palette_block.and_then(|palette_block| palette_block.get_entry(value)).unwrap() //safe unwrap since palette images always have a palette covering their pixels
+        }
+    }
+
+    //the type of the images this pixel can be stored in
+    pub fn pixel_type(&self) -> PixelType {
+        match self {
+            Pixel::Black | Pixel::White => PixelType::BlackAndWhite,
+            Pixel::Gray(_) => PixelType::GrayLevels,
+            Pixel::Palette(_) => PixelType::Palette,
+            Pixel::TwentyFourBitsColors(..) => PixelType::TwentyFourBitsColors
+        }
+    }
+
+    //packs 8 black and white pixels per byte from the most significant bit, set_pixel (white in MiniPNG, PNG and BMP, black in PBM) giving the 1 bits
+    //the last byte is padded with 0 bits if needed
+    pub(crate) fn pack_bits(pixels: &[Pixel], set_pixel: Pixel) -> Vec<u8> {
+        pixels.chunks(8)
+              .map(|chunk| chunk.iter()
+                                .enumerate()
+                                .fold(0u8, |byte, (i, pixel)| if *pixel == set_pixel { byte | (1 << (7 - i)) } else { byte }))
+              .collect()
+    }
+}
+
 impl TryFrom<u8> for PixelType {
-    type Error = anyhow::Error;
+    type Error = MiniPngError;
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+    fn try_from(value: u8) -> Result<Self> {
         Ok(match value {
             0 => PixelType::BlackAndWhite,
             1 => PixelType::GrayLevels,
             2 => PixelType::Palette,
             3 => PixelType::TwentyFourBitsColors,
-            _ => return Err(anyhow!("Unable to parse the pixel type: {} is not a valid pixel type.", value))
+            _ => return Err(MiniPngError::InvalidPixelType { value, offset: 0 })
         })
     }
 }
 
+impl From<PixelType> for u8 {
+    fn from(pixel_type: PixelType) -> u8 {
+        match pixel_type {
+            PixelType::BlackAndWhite => 0,
+            PixelType::GrayLevels => 1,
+            PixelType::Palette => 2,
+            PixelType::TwentyFourBitsColors => 3
+        }
+    }
+}
+
 impl fmt::Display for PixelType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -0,0 +1,152 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use png::{BitDepth, ColorType, Decoder, Encoder, Transformations};
+
+use super::comment_block::CommentBlock;
+use super::error::{MiniPngError, Result};
+use super::header_block::HeaderBlock;
+use super::mini_png::MiniPNG;
+use super::palette_block::PaletteBlock;
+use super::pixel::{Pixel, PixelType};
+
+pub fn to_png(mini_png: &MiniPNG, writer: impl Write) -> Result<()> {
+    let mut encoder = Encoder::new(writer, mini_png.get_image_width(), mini_png.get_image_height());
+
+    //each pixel type is mapped to the most compact PNG colour type able to represent it exactly
+    let (color_type, bit_depth) = match mini_png.get_pixel_type() {
+        PixelType::BlackAndWhite => (ColorType::Grayscale, BitDepth::One),
+        PixelType::GrayLevels => (ColorType::Grayscale, BitDepth::Eight),
+        PixelType::Palette => (ColorType::Indexed, BitDepth::Eight),
+        PixelType::TwentyFourBitsColors => (ColorType::Rgb, BitDepth::Eight)
+    };
+
+    encoder.set_color(color_type);
+    encoder.set_depth(bit_depth);
+
+    if color_type == ColorType::Indexed {
+        let palette = mini_png.get_palette().unwrap(); //safe unwrap since palette images always have a palette
+        encoder.set_palette(palette.to_bytes());
+    }
+
+    for comment in mini_png.get_comments() {
+        encoder.add_text_chunk(String::from("Comment"), comment)?;
+    }
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&png_image_data(mini_png))?;
+    writer.finish()?;
+
+    Ok(())
+}
+
+pub fn save_png(mini_png: &MiniPNG, file_path: &Path) -> Result<()> {
+    let file = File::create(file_path)?;
+
+    to_png(mini_png, BufWriter::new(file))
+}
+
+pub fn from_png(reader: impl Read) -> Result<MiniPNG> {
+    let mut decoder = Decoder::new(reader);
+    decoder.set_transformations(Transformations::IDENTITY); //we want the samples exactly as they are stored
+
+    let mut reader = decoder.read_info()?;
+    let info = reader.info();
+
+    let (image_width, image_height) = (info.width, info.height);
+    let (color_type, bit_depth) = (info.color_type, info.bit_depth);
+
+    if matches!(color_type, ColorType::GrayscaleAlpha | ColorType::Rgba) || info.trns.is_some() {
+        return Err(MiniPngError::UnsupportedPngFeature(String::from("transparency")));
+    }
+
+    if bit_depth == BitDepth::Sixteen {
+        return Err(MiniPngError::UnsupportedPngFeature(String::from("16 bits samples")));
+    }
+
+    let pixel_type = match (color_type, bit_depth) {
+        (ColorType::Grayscale, BitDepth::One) => PixelType::BlackAndWhite,
+        (ColorType::Grayscale, _) => PixelType::GrayLevels,
+        (ColorType::Indexed, _) => PixelType::Palette,
+        _ => PixelType::TwentyFourBitsColors
+    };
+
+    let palette_block = match (pixel_type, &info.palette) {
+        (PixelType::Palette, Some(palette)) => Some(PaletteBlock::try_from(palette.as_ref())?),
+        _ => None
+    };
+
+    let comment_blocks = info.uncompressed_latin1_text.iter()
+                                                      .filter(|chunk| chunk.keyword == "Comment" && !chunk.text.is_empty()) //MiniPNG blocks cannot be empty
+                                                      .map(|chunk| CommentBlock::from_text(&chunk.text))
+                                                      .collect();
+
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let output_info = reader.next_frame(&mut buffer)?;
+
+    let bits_per_sample = bit_depth as usize;
+    let max_sample = (1u16 << bits_per_sample) - 1;
+    let palette_entries_count = palette_block.as_ref().map(|palette_block| palette_block.entries().len());
+    let mut pixels = Vec::with_capacity(image_width as usize * image_height as usize);
+
+    for row in buffer.chunks(output_info.line_size).take(image_height as usize) {
+        for y in 0..image_width as usize {
+            let pixel = match pixel_type {
+                PixelType::TwentyFourBitsColors => Pixel::TwentyFourBitsColors(row[3 * y], row[3 * y + 1], row[3 * y + 2]),
+                _ => {
+                    //samples narrower than a byte are packed from the most significant bit
+                    let bit_index = y * bits_per_sample;
+                    let sample = (row[bit_index / 8] >> (8 - bits_per_sample - bit_index % 8)) & max_sample as u8;
+
+                    match pixel_type {
+                        PixelType::BlackAndWhite => if sample == 1 { Pixel::White } else { Pixel::Black },
+                        PixelType::GrayLevels => Pixel::Gray((sample as u16 * 255 / max_sample) as u8),
+                        _ => Pixel::Palette(sample)
+                    }
+                }
+            };
+
+            if let (Pixel::Palette(index), Some(palette_entries_count)) = (pixel, palette_entries_count) {
+                if index as usize >= palette_entries_count {
+                    return Err(MiniPngError::UnsupportedPngFeature(format!("pixels referencing the missing palette entry {}", index)));
+                }
+            }
+
+            pixels.push(pixel);
+        }
+    }
+
+    Ok(MiniPNG::from_parts(HeaderBlock::new(image_width, image_height, pixel_type), comment_blocks, palette_block, pixels))
+}
+
+pub fn from_png_file(file_path: &Path) -> Result<MiniPNG> {
+    let file = File::open(file_path)?;
+
+    from_png(BufReader::new(file))
+}
+
+//unlike MiniPNG, PNG pads every row (instead of the whole image) to a byte boundary
+fn png_image_data(mini_png: &MiniPNG) -> Vec<u8> {
+    let mut data = Vec::new();
+
+    for x in 0..mini_png.get_image_height() {
+        let row: Vec<Pixel> = (0..mini_png.get_image_width()).map(|y| mini_png.get_pixel_at(x, y).unwrap()) //safe unwrap since we cannot go out of bounds
+                                                              .collect();
+
+        match mini_png.get_pixel_type() {
+            PixelType::BlackAndWhite => data.extend(Pixel::pack_bits(&row, Pixel::White)),
+            PixelType::GrayLevels | PixelType::Palette | PixelType::TwentyFourBitsColors => {
+                for pixel in row {
+                    match pixel {
+                        Pixel::Black | Pixel::White => unreachable!(),
+                        Pixel::Gray(value) | Pixel::Palette(value) => data.push(value),
+                        Pixel::TwentyFourBitsColors(r, g, b) => data.extend_from_slice(&[r, g, b])
+                    }
+                }
+            }
+        }
+    }
+
+    data
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+type Color = (u8, u8, u8);
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Quantizer {
+    #[default]
+    MedianCut,
+    Octree
+}
+
+//builds a palette of at most max_entries colors (at least 1) representing the given colors
+pub fn quantize(colors: &[Color], max_entries: usize, quantizer: Quantizer) -> Vec<Color> {
+    let max_entries = max_entries.max(1);
+
+    let mut histogram = HashMap::<Color, u64>::new();
+    for color in colors {
+        *histogram.entry(*color).or_insert(0) += 1;
+    }
+
+    //sorting makes the result independent of the hash map iteration order
+    let mut histogram: Vec<(Color, u64)> = histogram.into_iter().collect();
+    histogram.sort_unstable();
+
+    if histogram.len() <= max_entries {
+        return histogram.into_iter().map(|(color, _)| color).collect();
+    }
+
+    match quantizer {
+        Quantizer::MedianCut => median_cut(histogram, max_entries),
+        Quantizer::Octree => octree(&histogram, max_entries)
+    }
+}
+
+fn channel(color: Color, index: usize) -> u8 {
+    match index {
+        0 => color.0,
+        1 => color.1,
+        _ => color.2
+    }
+}
+
+fn average_color(colors: &[(Color, u64)]) -> Color {
+    let total: u64 = colors.iter().map(|(_, count)| count).sum();
+    let sum = |index: usize| colors.iter().map(|(color, count)| channel(*color, index) as u64 * count).sum::<u64>();
+
+    (((sum(0) + total / 2) / total) as u8, ((sum(1) + total / 2) / total) as u8, ((sum(2) + total / 2) / total) as u8)
+}
+
+//returns the widest channel of a box and its range
+fn widest_channel(colors: &[(Color, u64)]) -> (usize, u8) {
+    (0..3).map(|index| {
+              let min = colors.iter().map(|(color, _)| channel(*color, index)).min().unwrap(); //safe unwrap since boxes are never empty
+              let max = colors.iter().map(|(color, _)| channel(*color, index)).max().unwrap(); //ditto
+              (index, max - min)
+          })
+          .max_by_key(|(_, range)| *range)
+          .unwrap() //safe unwrap since there are 3 channels
+}
+
+fn median_cut(histogram: Vec<(Color, u64)>, max_entries: usize) -> Vec<Color> {
+    let mut boxes = vec![histogram];
+
+    while boxes.len() < max_entries {
+        //the box holding distinct colors with the widest range is split at the median of its population
+        let splittable_box = (0..boxes.len()).filter(|i| boxes[*i].len() > 1)
+                                             .max_by_key(|i| widest_channel(&boxes[*i]).1);
+
+        let Some(i) = splittable_box else {
+            break;
+        };
+
+        let mut colors = boxes.swap_remove(i);
+        let (index, _) = widest_channel(&colors);
+        colors.sort_unstable_by_key(|(color, _)| channel(*color, index));
+
+        let total: u64 = colors.iter().map(|(_, count)| count).sum();
+        let mut population = 0;
+        let mut median = colors.len() - 1;
+        for (j, (_, count)) in colors.iter().enumerate() {
+            population += count;
+            if population * 2 >= total {
+                median = j;
+                break;
+            }
+        }
+
+        //both halves must keep at least one color
+        let split_index = (median + 1).clamp(1, colors.len() - 1);
+        let upper_half = colors.split_off(split_index);
+
+        boxes.push(colors);
+        boxes.push(upper_half);
+    }
+
+    boxes.iter().map(|colors| average_color(colors)).collect()
+}
+
+#[derive(Default)]
+struct OctreeNode {
+    children: [Option<usize>; 8],
+    count: u64,
+    sums: [u64; 3],
+    is_leaf: bool
+}
+
+const OCTREE_DEPTH: usize = 8;
+
+fn octree(histogram: &[(Color, u64)], max_entries: usize) -> Vec<Color> {
+    let mut nodes = vec![OctreeNode::default()]; //nodes[0] is the root
+    let mut reducible_nodes: Vec<Vec<usize>> = vec![Vec::new(); OCTREE_DEPTH]; //inner nodes, by depth
+    let mut leaves_count = 0;
+
+    for ((r, g, b), count) in histogram {
+        let mut node = 0;
+
+        for depth in 0..OCTREE_DEPTH {
+            let shift = 7 - depth;
+            let child_index = ((((r >> shift) & 1) << 2) | (((g >> shift) & 1) << 1) | ((b >> shift) & 1)) as usize;
+
+            node = match nodes[node].children[child_index] {
+                Some(child) => child,
+                None => {
+                    nodes.push(OctreeNode::default());
+                    let child = nodes.len() - 1;
+                    nodes[node].children[child_index] = Some(child);
+
+                    if depth == OCTREE_DEPTH - 1 {
+                        nodes[child].is_leaf = true;
+                        leaves_count += 1;
+                    } else {
+                        reducible_nodes[depth + 1].push(child);
+                    }
+
+                    child
+                }
+            };
+        }
+
+        nodes[node].count += count;
+        nodes[node].sums[0] += *r as u64 * count;
+        nodes[node].sums[1] += *g as u64 * count;
+        nodes[node].sums[2] += *b as u64 * count;
+    }
+
+    //the deepest inner nodes are turned into leaves, merging their children, until there are few enough leaves
+    reducible_nodes[0].push(0);
+    while leaves_count > max_entries {
+        let depth = (0..OCTREE_DEPTH).rev()
+                                     .find(|depth| !reducible_nodes[*depth].is_empty())
+                                     .unwrap(); //safe unwrap since the root can always be reduced into a single leaf
+        let node = reducible_nodes[depth].pop().unwrap(); //safe unwrap since we checked the list is not empty
+
+        let mut merged_children = 0;
+        for child in nodes[node].children.into_iter().flatten() {
+            let (count, sums) = (nodes[child].count, nodes[child].sums);
+            nodes[child].is_leaf = false;
+
+            nodes[node].count += count;
+            for (i, sum) in sums.iter().enumerate() {
+                nodes[node].sums[i] += sum;
+            }
+
+            merged_children += 1;
+        }
+
+        nodes[node].children = [None; 8];
+        nodes[node].is_leaf = true;
+        leaves_count = leaves_count + 1 - merged_children;
+    }
+
+    nodes.iter()
+         .filter(|node| node.is_leaf && node.count > 0)
+         .map(|node| {
+             let average = |sum: u64| ((sum + node.count / 2) / node.count) as u8;
+             (average(node.sums[0]), average(node.sums[1]), average(node.sums[2]))
+         })
+         .collect()
+}
@@ -0,0 +1,292 @@
+use super::mini_png::MiniPNG;
+
+#[derive(Clone, Copy, Debug)]
+pub struct RenderOptions {
+    pub zoom: u32, //each pixel of the image becomes a zoom × zoom square, 0 is treated as 1
+    pub shrink: u32, //each shrink × shrink square of the image is reduced to its top left pixel (applied after the zoom), 0 is treated as 1
+    pub background: (u8, u8, u8), //color of the framebuffer around the image
+    pub viewport: Option<(u32, u32)>, //width and height of the framebuffer, the image is centered in it (and cropped if it does not fit), None to fit the scaled image exactly
+    pub offset: (i32, i32), //horizontal and vertical shift of the image from the center of the viewport, in framebuffer pixels
+    pub grid: Option<(u8, u8, u8)> //color of the lines drawn along the top and left edges of each pixel, ignored unless zoom > 1
+}
+
+impl Default for RenderOptions {
+    fn default() -> RenderOptions {
+        RenderOptions {
+            zoom: 1,
+            shrink: 1,
+            background: (0, 0, 0),
+            viewport: None,
+            offset: (0, 0),
+            grid: None
+        }
+    }
+}
+
+//an RGB image stored row by row, 3 bytes per pixel
+pub struct Framebuffer {
+    width: u32,
+    height: u32,
+    bytes: Vec<u8>
+}
+
+//5 × 7 glyphs, one byte per row with the leftmost dot as the fifth bit, lowercase letters are drawn as uppercase ones
+const GLYPHS: [(char, [u8; 7]); 59] = [
+    ('A', [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001]),
+    ('B', [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110]),
+    ('C', [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110]),
+    ('D', [0b11110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11110]),
+    ('E', [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111]),
+    ('F', [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000]),
+    ('G', [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111]),
+    ('H', [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001]),
+    ('I', [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('J', [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100]),
+    ('K', [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001]),
+    ('L', [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111]),
+    ('M', [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001]),
+    ('N', [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001]),
+    ('O', [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110]),
+    ('P', [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000]),
+    ('Q', [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101]),
+    ('R', [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001]),
+    ('S', [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110]),
+    ('T', [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100]),
+    ('U', [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110]),
+    ('V', [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100]),
+    ('W', [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010]),
+    ('X', [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001]),
+    ('Y', [0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100, 0b00100]),
+    ('Z', [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111]),
+    ('0', [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110]),
+    ('1', [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('2', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111]),
+    ('3', [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110]),
+    ('4', [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010]),
+    ('5', [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110]),
+    ('6', [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110]),
+    ('7', [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000]),
+    ('8', [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110]),
+    ('9', [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100]),
+    ('.', [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100]),
+    (',', [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000]),
+    (':', [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000]),
+    (';', [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b00100, 0b01000]),
+    ('!', [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100]),
+    ('?', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100]),
+    ('\'', [0b00100, 0b00100, 0b01000, 0b00000, 0b00000, 0b00000, 0b00000]),
+    ('"', [0b01010, 0b01010, 0b01010, 0b00000, 0b00000, 0b00000, 0b00000]),
+    ('(', [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010]),
+    (')', [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000]),
+    ('[', [0b01110, 0b01000, 0b01000, 0b01000, 0b01000, 0b01000, 0b01110]),
+    (']', [0b01110, 0b00010, 0b00010, 0b00010, 0b00010, 0b00010, 0b01110]),
+    ('-', [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000]),
+    ('_', [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111]),
+    ('/', [0b00001, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b10000]),
+    ('\\', [0b10000, 0b10000, 0b01000, 0b00100, 0b00010, 0b00001, 0b00001]),
+    ('+', [0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000]),
+    ('=', [0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000]),
+    ('#', [0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010]),
+    ('%', [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011]),
+    ('<', [0b00010, 0b00100, 0b01000, 0b10000, 0b01000, 0b00100, 0b00010]),
+    ('>', [0b01000, 0b00100, 0b00010, 0b00001, 0b00010, 0b00100, 0b01000]),
+    ('*', [0b00000, 0b00100, 0b10101, 0b01110, 0b10101, 0b00100, 0b00000])
+];
+
+//each glyph is followed by a blank column and a blank row
+pub const GLYPH_WIDTH: u32 = 6;
+pub const GLYPH_HEIGHT: u32 = 8;
+
+impl Framebuffer {
+    pub fn new(width: u32, height: u32, (r, g, b): (u8, u8, u8)) -> Framebuffer {
+        Framebuffer {
+            width,
+            height,
+            bytes: [r, g, b].repeat(width as usize * height as usize)
+        }
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    //like in MiniPNG::get_rgb_at, x designates a row and y a column
+    pub fn get_rgb_at(&self, x: u32, y: u32) -> Option<(u8, u8, u8)> {
+        if y >= self.width || x >= self.height {
+            return None;
+        }
+
+        let i = 3 * (self.width as usize * x as usize + y as usize);
+        Some((self.bytes[i], self.bytes[i + 1], self.bytes[i + 2]))
+    }
+
+    //borrowed rather than cloned since the buffer is meant to be copied as a whole to a texture or a file
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    //number of bytes per row
+    pub fn pitch(&self) -> usize {
+        3 * self.width as usize
+    }
+
+    //the parts out of the framebuffer are ignored
+    pub fn set_rgb_at(&mut self, x: u32, y: u32, (r, g, b): (u8, u8, u8)) {
+        if y < self.width && x < self.height {
+            let i = 3 * (self.width as usize * x as usize + y as usize);
+            self.bytes[i..i + 3].copy_from_slice(&[r, g, b]);
+        }
+    }
+
+    //the top left corner is at row x and column y
+    pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: (u8, u8, u8)) {
+        for row in x..x.saturating_add(height).min(self.height) {
+            for column in y..y.saturating_add(width).min(self.width) {
+                self.set_rgb_at(row, column, color);
+            }
+        }
+    }
+
+    //writes text starting at row x and column y, each dot of the font being a scale × scale square
+    //lines are separated by '\n' and the characters missing from the font are drawn as '?'
+    pub fn draw_text(&mut self, x: u32, y: u32, text: &str, color: (u8, u8, u8), scale: u32) {
+        for (line_index, line) in text.lines().enumerate() {
+            for (char_index, char) in line.chars().enumerate() {
+                let rows = glyph(char);
+                let glyph_x = x + line_index as u32 * GLYPH_HEIGHT * scale;
+                let glyph_y = y + char_index as u32 * GLYPH_WIDTH * scale;
+
+                for (row_index, row) in rows.iter().enumerate() {
+                    for column_index in 0..5 {
+                        if row & (0b10000 >> column_index) != 0 {
+                            self.fill_rect(glyph_x + row_index as u32 * scale, glyph_y + column_index * scale, scale, scale, color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn glyph(char: char) -> [u8; 7] {
+    if char == ' ' {
+        return [0; 7];
+    }
+
+    let char = char.to_ascii_uppercase();
+    let find = |char: char| GLYPHS.iter().find(|(glyph_char, _)| *glyph_char == char).map(|(_, rows)| *rows);
+
+    find(char).or_else(|| find('?')).unwrap() //safe unwrap since '?' is in the font
+}
+
+//splits the text into lines of at most max_chars characters, breaking between words when possible
+pub fn wrap_text(text: &str, max_chars: usize) -> String {
+    let max_chars = max_chars.max(1);
+    let mut lines = Vec::<String>::new();
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+
+        for word in paragraph.split(' ') {
+            let mut word: Vec<char> = word.chars().collect();
+
+            if !line.is_empty() && line.chars().count() + 1 + word.len() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+
+            if !line.is_empty() {
+                line.push(' ');
+            }
+
+            //the words longer than a line are cut
+            while word.len() > max_chars - line.chars().count() {
+                let rest = word.split_off(max_chars - line.chars().count());
+                line.extend(word);
+                lines.push(std::mem::take(&mut line));
+                word = rest;
+            }
+
+            line.extend(word);
+        }
+
+        lines.push(line);
+    }
+
+    lines.join("\n")
+}
+
+//width and height of the image once zoomed and shrunk, a partial square still gives a pixel
+pub fn scaled_size(image: &MiniPNG, options: &RenderOptions) -> (u32, u32) {
+    let (zoom, shrink) = (options.zoom.max(1) as u64, options.shrink.max(1) as u64);
+    let scale = |length: u32| (length as u64 * zoom).div_ceil(shrink) as u32;
+
+    (scale(image.get_image_width()), scale(image.get_image_height()))
+}
+
+//the size of the framebuffer and the position of the top left corner of the image in it, negative if the image does not fit
+fn placement(image: &MiniPNG, options: &RenderOptions) -> ((u32, u32), (i64, i64)) {
+    let (scaled_width, scaled_height) = scaled_size(image, options);
+    let (width, height) = options.viewport.unwrap_or((scaled_width, scaled_height));
+
+    let top = (height as i64 - scaled_height as i64) / 2 + options.offset.1 as i64;
+    let left = (width as i64 - scaled_width as i64) / 2 + options.offset.0 as i64;
+
+    ((width, height), (top, left))
+}
+
+//the position (row, column) of the top left corner of the image in the framebuffer, negative if the image does not fit
+pub fn image_origin(image: &MiniPNG, options: &RenderOptions) -> (i64, i64) {
+    placement(image, options).1
+}
+
+//the pixel of the image (row, column) drawn at row x and column y of the framebuffer, None if it is the background
+pub fn image_position(image: &MiniPNG, options: &RenderOptions, x: i64, y: i64) -> Option<(u32, u32)> {
+    let (zoom, shrink) = (options.zoom.max(1) as i64, options.shrink.max(1) as i64);
+    let (_, (top, left)) = placement(image, options);
+
+    if x < top || y < left {
+        return None;
+    }
+
+    let (image_x, image_y) = ((x - top) * shrink / zoom, (y - left) * shrink / zoom);
+    if image_x >= image.get_image_height() as i64 || image_y >= image.get_image_width() as i64 {
+        return None;
+    }
+
+    Some((image_x as u32, image_y as u32))
+}
+
+//draws the image into a new framebuffer, only the visible pixels are looked up
+pub fn render(image: &MiniPNG, options: &RenderOptions) -> Framebuffer {
+    let (zoom, shrink) = (options.zoom.max(1) as i64, options.shrink.max(1) as i64);
+    let (scaled_width, scaled_height) = scaled_size(image, options);
+    let (scaled_width, scaled_height) = (scaled_width as i64, scaled_height as i64);
+    let ((width, height), (top, left)) = placement(image, options);
+
+    let grid = options.grid.filter(|_| zoom > 1);
+
+    let mut framebuffer = Framebuffer::new(width, height, options.background);
+
+    let palette_block = image.get_palette();
+    for x in top.max(0)..(top + scaled_height).min(height as i64) {
+        let image_x = ((x - top) * shrink / zoom) as u32;
+
+        for y in left.max(0)..(left + scaled_width).min(width as i64) {
+            let image_y = ((y - left) * shrink / zoom) as u32;
+            let color = match grid {
+                Some(grid_color) if (x - top) % zoom == 0 || (y - left) % zoom == 0 => grid_color,
+                _ => image.get_pixel_at(image_x, image_y)
+                          .unwrap() //safe unwrap since only the pixels of the image are visited
+                          .to_rgb(palette_block.as_ref())
+            };
+
+            framebuffer.set_rgb_at(x as u32, y as u32, color);
+        }
+    }
+
+    framebuffer
+}
@@ -0,0 +1,117 @@
+use std::f32::consts::PI;
+
+use super::header_block::HeaderBlock;
+use super::mini_png::MiniPNG;
+use super::pixel::{Pixel, PixelType};
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Filter {
+    #[default]
+    Nearest,
+    Bilinear,
+    Lanczos //with 3 lobes, sharper than Bilinear but may ring around edges
+}
+
+fn triangle(x: f32) -> f32 {
+    (1.0 - x.abs()).max(0.0)
+}
+
+fn lanczos3(x: f32) -> f32 {
+    if x == 0.0 {
+        1.0
+    } else if x.abs() < 3.0 {
+        let px = PI * x;
+        3.0 * px.sin() * (px / 3.0).sin() / (px * px)
+    } else {
+        0.0
+    }
+}
+
+//for each destination coordinate, the source coordinate it is copied from
+fn nearest_indexes(source_length: u32, destination_length: u32) -> Vec<usize> {
+    (0..destination_length as u64).map(|i| ((2 * i + 1) * source_length as u64 / (2 * destination_length as u64)) as usize)
+                                  .collect()
+}
+
+//for each destination coordinate, the source coordinates contributing to it and their normalised weights
+fn weights(source_length: u32, destination_length: u32, kernel: fn(f32) -> f32, radius: f32) -> Vec<Vec<(usize, f32)>> {
+    let scale = source_length as f32 / destination_length as f32;
+    let stretch = scale.max(1.0); //the kernel is widened when shrinking, otherwise some source pixels would be ignored
+
+    (0..destination_length).map(|i| {
+                               let center = (i as f32 + 0.5) * scale - 0.5;
+                               let first = (center - radius * stretch).floor() as i64;
+                               let last = (center + radius * stretch).ceil() as i64;
+
+                               //the pixels on the edges are repeated outside of the image
+                               let mut weights: Vec<(usize, f32)> = (first..=last).map(|j| (j.clamp(0, source_length as i64 - 1) as usize, kernel((j as f32 - center) / stretch)))
+                                                                                  .filter(|(_, weight)| *weight != 0.0)
+                                                                                  .collect();
+
+                               let total: f32 = weights.iter().map(|(_, weight)| weight).sum();
+                               weights.iter_mut().for_each(|(_, weight)| *weight /= total);
+
+                               weights
+                           })
+                           .collect()
+}
+
+fn weighted_sum(weights: &[(usize, f32)], value_at: impl Fn(usize) -> [f32; 3]) -> [f32; 3] {
+    weights.iter().fold([0.0; 3], |sum, (i, weight)| {
+        let value = value_at(*i);
+        [sum[0] + value[0] * weight, sum[1] + value[1] * weight, sum[2] + value[2] * weight]
+    })
+}
+
+fn to_channel(value: f32) -> u8 {
+    value.round().clamp(0.0, 255.0) as u8
+}
+
+impl MiniPNG {
+    //returns None if a dimension is 0, palette and black and white images are always resized with Nearest since interpolating would create values they cannot hold
+    pub fn resize(&self, width: u32, height: u32, filter: Filter) -> Option<MiniPNG> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let pixel_type = self.get_pixel_type();
+        let header_block = HeaderBlock::new(width, height, pixel_type);
+
+        let kernel: (fn(f32) -> f32, f32) = match (pixel_type, filter) {
+            (PixelType::BlackAndWhite | PixelType::Palette, _) | (_, Filter::Nearest) => {
+                let rows = self.rows();
+                let columns = nearest_indexes(self.get_image_width(), width);
+                let pixels = nearest_indexes(self.get_image_height(), height).into_iter()
+                                                                             .flat_map(|x| columns.iter().map(|y| rows[x][*y]).collect::<Vec<Pixel>>())
+                                                                             .collect();
+
+                return Some(self.with_pixels(header_block, self.get_palette(), pixels));
+            },
+            (_, Filter::Bilinear) => (triangle, 1.0),
+            (_, Filter::Lanczos) => (lanczos3, 3.0)
+        };
+
+        let rows: Vec<Vec<[f32; 3]>> = self.rgb_rows()
+                                           .into_iter()
+                                           .map(|row| row.into_iter().map(|(r, g, b)| [r as f32, g as f32, b as f32]).collect())
+                                           .collect();
+
+        //the image is resized horizontally, then vertically
+        let column_weights = weights(self.get_image_width(), width, kernel.0, kernel.1);
+        let rows: Vec<Vec<[f32; 3]>> = rows.iter()
+                                           .map(|row| column_weights.iter()
+                                                                    .map(|weights| weighted_sum(weights, |y| row[y]))
+                                                                    .collect())
+                                           .collect();
+
+        let mut pixels = Vec::with_capacity(width as usize * height as usize);
+        for weights in weights(self.get_image_height(), height, kernel.0, kernel.1) {
+            pixels.extend((0..width as usize).map(|y| match weighted_sum(&weights, |x| rows[x][y]) {
+                [value, _, _] if pixel_type == PixelType::GrayLevels => Pixel::Gray(to_channel(value)),
+                [r, g, b] => Pixel::TwentyFourBitsColors(to_channel(r), to_channel(g), to_channel(b))
+            }));
+        }
+
+        Some(self.with_pixels(header_block, None, pixels))
+    }
+}
@@ -1,2 +1,2 @@
 mod implem;
-pub use implem::{block, comment_block, data_block, header_block, mini_png, palette_block, pixel};
\ No newline at end of file
+pub use implem::{block, blocks, bmp, checksum_block, comment_block, conversion, data_block, decode_options, dithering, encode_options, error, geometry, header_block, mini_png, netpbm, palette_block, pixel, png_codec, quantization, render, resampling};
\ No newline at end of file
@@ -0,0 +1,77 @@
+mod common;
+
+use mini_png::bmp;
+use mini_png::error::MiniPngError;
+use mini_png::mini_png::MiniPNG;
+use mini_png::pixel::Pixel;
+
+//a 24 bits BMP file made of its headers only
+fn bmp_headers(image_width: i32, image_height: i32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"BM");
+    bytes.extend_from_slice(&54u32.to_le_bytes()); //file length
+    bytes.extend_from_slice(&[0; 4]);
+    bytes.extend_from_slice(&54u32.to_le_bytes()); //pixel data offset
+    bytes.extend_from_slice(&40u32.to_le_bytes()); //info header length
+    bytes.extend_from_slice(&image_width.to_le_bytes());
+    bytes.extend_from_slice(&image_height.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); //planes
+    bytes.extend_from_slice(&24u16.to_le_bytes()); //bits per pixel
+    bytes.extend_from_slice(&[0; 24]); //compression, sizes, resolutions and color counts
+
+    bytes
+}
+
+#[test]
+fn huge_dimensions_are_rejected_before_allocating() {
+    for (image_width, image_height) in [(i32::MAX, i32::MAX), (i32::MAX, -i32::MAX), (1, 1)] {
+        let result = bmp::from_bmp(&bmp_headers(image_width, image_height)[..]);
+
+        assert!(matches!(result, Err(MiniPngError::InvalidBmp { offset: 54, .. })));
+    }
+}
+
+#[test]
+fn images_of_every_pixel_type_survive_a_bmp_round_trip() {
+    //3 pixels wide, so that every row is padded
+    common::assert_round_trips(3, 2, |image| {
+        let mut bytes = Vec::new();
+        bmp::to_bmp(image, &mut bytes).unwrap();
+
+        let imported = bmp::from_bmp(&bytes[..]).unwrap();
+        assert_eq!(imported.get_pixel_type(), image.get_pixel_type());
+
+        imported
+    });
+}
+
+#[test]
+fn rows_are_written_bottom_up_and_padded_to_4_bytes() {
+    let image = MiniPNG::from_pixels(1, 2, vec![Pixel::TwentyFourBitsColors(1, 2, 3), Pixel::TwentyFourBitsColors(4, 5, 6)]).unwrap();
+
+    let mut bytes = Vec::new();
+    bmp::to_bmp(&image, &mut bytes).unwrap();
+
+    assert_eq!(bytes.len(), 54 + 2 * 4);
+    assert_eq!(bytes[54..], [6, 5, 4, 0, 3, 2, 1, 0]);
+}
+
+#[test]
+fn top_down_files_are_read_in_order() {
+    let mut bytes = bmp_headers(1, -2);
+    bytes.extend_from_slice(&[3, 2, 1, 0, 6, 5, 4, 0]);
+
+    let image = bmp::from_bmp(&bytes[..]).unwrap();
+
+    assert_eq!(image.get_pixel_at(0, 0), Some(Pixel::TwentyFourBitsColors(1, 2, 3)));
+    assert_eq!(image.get_pixel_at(1, 0), Some(Pixel::TwentyFourBitsColors(4, 5, 6)));
+}
+
+#[test]
+fn empty_dimensions_are_rejected() {
+    for (image_width, image_height) in [(0, 1), (-1, 1), (1, 0)] {
+        let result = bmp::from_bmp(&bmp_headers(image_width, image_height)[..]);
+
+        assert!(matches!(result, Err(MiniPngError::InvalidBmp { reason: "the width must be positive and the height different from 0", offset: 18 })));
+    }
+}
@@ -0,0 +1,35 @@
+use mini_png::mini_png::MiniPNG;
+use mini_png::pixel::Pixel;
+
+//an image of each pixel type with the given dimensions, in the order BlackAndWhite, GrayLevels, Palette and TwentyFourBitsColors
+pub fn sample_images(image_width: u32, image_height: u32) -> Vec<MiniPNG> {
+    let pixels = |pixel: fn(u32) -> Pixel| (0..image_width * image_height).map(pixel).collect::<Vec<Pixel>>();
+
+    let black_and_white = MiniPNG::from_pixels(image_width, image_height, pixels(|i| if i % 3 == 0 { Pixel::Black } else { Pixel::White })).unwrap();
+    let gray_levels = MiniPNG::from_pixels(image_width, image_height, pixels(|i| Pixel::Gray((i * 37 % 256) as u8))).unwrap();
+    let palette = MiniPNG::from_pixels(image_width, image_height, pixels(|i| Pixel::Palette((i % 3) as u8))).unwrap()
+                                                                                                          .with_palette(vec![(255, 0, 0), (0, 255, 0), (0, 0, 255)])
+                                                                                                          .unwrap();
+    let twenty_four_bits_colors = MiniPNG::from_pixels(image_width, image_height, pixels(|i| Pixel::TwentyFourBitsColors((i % 256) as u8, ((i + 100) % 256) as u8, ((i + 200) % 256) as u8))).unwrap();
+
+    vec![black_and_white, gray_levels, palette, twenty_four_bits_colors]
+}
+
+//checks that every sample image comes back from the round trip with the same colors, and unchanged if it keeps its pixel type (palettes may be expanded)
+pub fn assert_round_trips(image_width: u32, image_height: u32, round_trip: impl Fn(&MiniPNG) -> MiniPNG) {
+    for image in sample_images(image_width, image_height) {
+        let decoded = round_trip(&image);
+
+        assert_eq!((decoded.get_image_width(), decoded.get_image_height()), (image_width, image_height));
+
+        for x in 0..image_height {
+            for y in 0..image_width {
+                assert_eq!(decoded.get_rgb_at(x, y), image.get_rgb_at(x, y), "{} image, pixel ({}, {})", image.get_pixel_type(), x, y);
+            }
+        }
+
+        if decoded.get_pixel_type() == image.get_pixel_type() {
+            assert_eq!(decoded.to_bytes(), image.to_bytes());
+        }
+    }
+}
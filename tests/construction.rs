@@ -0,0 +1,73 @@
+use std::fs;
+
+use mini_png::error::MiniPngError;
+use mini_png::mini_png::MiniPNG;
+use mini_png::pixel::{Pixel, PixelType};
+
+#[test]
+fn built_image_can_be_saved_and_loaded_back() {
+    let mut image = MiniPNG::new(3, 2, PixelType::Palette).unwrap()
+                                                          .with_palette(vec![(0, 0, 0), (255, 0, 0)])
+                                                          .unwrap();
+    image.set_pixel_at(1, 2, Pixel::Palette(1)).unwrap();
+    image.add_comment("built from scratch").unwrap();
+
+    let file_path = std::env::temp_dir().join(format!("mini_png_construction_{}.mp", std::process::id()));
+    image.save(&file_path).unwrap();
+    let loaded = MiniPNG::from_file(&file_path);
+    fs::remove_file(&file_path).unwrap();
+
+    let loaded = loaded.unwrap();
+    assert_eq!((loaded.get_image_width(), loaded.get_image_height()), (3, 2));
+    assert_eq!(loaded.get_comments(), ["built from scratch"]);
+    assert_eq!(loaded.get_pixel_at(1, 2), Some(Pixel::Palette(1)));
+    assert_eq!(loaded.get_rgb_at(1, 2), Some((255, 0, 0)));
+    assert_eq!(loaded.get_rgb_at(0, 0), Some((0, 0, 0)));
+}
+
+#[test]
+fn empty_comments_are_rejected() {
+    let mut image = MiniPNG::new(1, 1, PixelType::GrayLevels).unwrap();
+
+    assert!(matches!(image.add_comment(""), Err(MiniPngError::EmptyComment)));
+    assert!(MiniPNG::from_bytes(&image.to_bytes()).unwrap().get_comments().is_empty());
+}
+
+#[test]
+fn invalid_constructions_are_rejected() {
+    assert!(matches!(MiniPNG::new(0, 2, PixelType::GrayLevels), Err(MiniPngError::InvalidDimensions { image_width: 0, image_height: 2 })));
+    assert!(matches!(MiniPNG::from_pixels(2, 0, Vec::new()), Err(MiniPngError::InvalidDimensions { image_width: 2, image_height: 0 })));
+    assert!(matches!(MiniPNG::from_pixels(2, 2, vec![Pixel::Black; 3]), Err(MiniPngError::PixelCountMismatch { expected: 4, actual: 3 })));
+    assert!(matches!(MiniPNG::from_pixels(2, 1, vec![Pixel::Gray(1), Pixel::White]), Err(MiniPngError::PixelTypeMismatch { pixel: Pixel::White, pixel_type: PixelType::GrayLevels })));
+}
+
+#[test]
+fn palettes_must_cover_the_pixels() {
+    let image = || MiniPNG::from_pixels(2, 1, vec![Pixel::Palette(0), Pixel::Palette(2)]).unwrap();
+
+    assert!(matches!(MiniPNG::new(1, 1, PixelType::GrayLevels).unwrap().with_palette(vec![(0, 0, 0)]), Err(MiniPngError::UnexpectedPalette { pixel_type: PixelType::GrayLevels })));
+    assert!(matches!(image().with_palette(Vec::new()), Err(MiniPngError::InvalidPaletteSize { count: 0 })));
+    assert!(matches!(image().with_palette(vec![(0, 0, 0); 257]), Err(MiniPngError::InvalidPaletteSize { count: 257 })));
+    assert!(matches!(image().with_palette(vec![(0, 0, 0); 2]), Err(MiniPngError::UndefinedPaletteEntry { index: 2, count: 2 })));
+
+    //the placeholder palette goes up to the greatest index used
+    assert_eq!(image().get_palette().unwrap().entries().len(), 3);
+    assert!(image().with_palette(vec![(0, 0, 0); 3]).is_ok());
+}
+
+#[test]
+fn invalid_pixel_writes_are_rejected() {
+    let mut image = MiniPNG::new(3, 2, PixelType::Palette).unwrap()
+                                                          .with_palette(vec![(0, 0, 0), (255, 0, 0)])
+                                                          .unwrap();
+
+    assert!(matches!(image.set_pixel_at(2, 0, Pixel::Palette(1)), Err(MiniPngError::PixelOutOfBounds { x: 2, y: 0 })));
+    assert!(matches!(image.set_pixel_at(0, 3, Pixel::Palette(1)), Err(MiniPngError::PixelOutOfBounds { x: 0, y: 3 })));
+    assert!(matches!(image.set_pixel_at(0, 0, Pixel::Gray(1)), Err(MiniPngError::PixelTypeMismatch { pixel: Pixel::Gray(1), pixel_type: PixelType::Palette })));
+    assert!(matches!(image.set_pixel_at(0, 0, Pixel::Palette(2)), Err(MiniPngError::UndefinedPaletteEntry { index: 2, count: 2 })));
+    assert!(matches!(image.add_comment("caf\u{e9}"), Err(MiniPngError::NonAsciiComment { .. })));
+
+    //failed writes leave the image unchanged
+    assert!((0..2).all(|x| (0..3).all(|y| image.get_pixel_at(x, y) == Some(Pixel::Palette(0)))));
+    assert!(image.get_comments().is_empty());
+}
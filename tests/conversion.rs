@@ -0,0 +1,78 @@
+use mini_png::conversion::{self, ConversionOptions};
+use mini_png::mini_png::MiniPNG;
+use mini_png::pixel::{Pixel, PixelType};
+
+const PIXEL_TYPES: [PixelType; 4] = [PixelType::BlackAndWhite, PixelType::GrayLevels, PixelType::Palette, PixelType::TwentyFourBitsColors];
+
+fn colorful_image() -> MiniPNG {
+    let mut image = MiniPNG::from_pixels(3, 2, vec![Pixel::TwentyFourBitsColors(255, 0, 0), Pixel::TwentyFourBitsColors(0, 255, 0), Pixel::TwentyFourBitsColors(0, 0, 255),
+                                                    Pixel::TwentyFourBitsColors(0, 0, 0), Pixel::TwentyFourBitsColors(127, 127, 127), Pixel::TwentyFourBitsColors(255, 255, 255)]).unwrap();
+    image.add_comment("converted").unwrap();
+
+    image
+}
+
+#[test]
+fn luminance_uses_the_bt601_weights() {
+    assert_eq!(conversion::luminance((255, 0, 0)), 76);
+    assert_eq!(conversion::luminance((0, 255, 0)), 150);
+    assert_eq!(conversion::luminance((0, 0, 255)), 29);
+    assert_eq!(conversion::luminance((255, 255, 255)), 255);
+}
+
+#[test]
+fn images_can_be_converted_between_every_pixel_type() {
+    for source_type in PIXEL_TYPES {
+        let source = colorful_image().convert(source_type, ConversionOptions::default());
+
+        for pixel_type in PIXEL_TYPES {
+            let converted = source.convert(pixel_type, ConversionOptions::default());
+
+            assert_eq!(converted.get_pixel_type(), pixel_type);
+            assert_eq!(converted.get_comments(), ["converted"]);
+            assert_eq!(converted.get_palette().is_some(), pixel_type == PixelType::Palette);
+
+            let decoded = MiniPNG::from_bytes(&converted.to_bytes()).unwrap();
+            assert_eq!(decoded.get_pixel_type(), pixel_type);
+        }
+    }
+}
+
+#[test]
+fn conversions_map_the_pixels() {
+    let image = colorful_image();
+
+    let gray_levels = image.convert(PixelType::GrayLevels, ConversionOptions::default());
+    assert_eq!(gray_levels.get_pixel_at(0, 0), Some(Pixel::Gray(76)));
+    assert_eq!(gray_levels.get_pixel_at(1, 1), Some(Pixel::Gray(127)));
+
+    //the threshold applies to the luminance
+    let black_and_white = image.convert(PixelType::BlackAndWhite, ConversionOptions::default());
+    let pixels: Vec<Option<Pixel>> = (0..3).map(|y| black_and_white.get_pixel_at(0, y)).collect();
+    assert_eq!(pixels, [Some(Pixel::Black), Some(Pixel::White), Some(Pixel::Black)]);
+
+    let black_and_white = image.convert(PixelType::BlackAndWhite, ConversionOptions { threshold: 127, ..ConversionOptions::default() });
+    assert_eq!(black_and_white.get_pixel_at(1, 1), Some(Pixel::White));
+
+    //the six colors fit in the palette, so they are all kept exactly
+    let palette = image.convert(PixelType::Palette, ConversionOptions::default());
+    assert_eq!(palette.get_palette().unwrap().entries().len(), 6);
+    for x in 0..2 {
+        for y in 0..3 {
+            assert_eq!(palette.get_rgb_at(x, y), image.get_rgb_at(x, y));
+        }
+    }
+
+    let twenty_four_bits_colors = black_and_white.convert(PixelType::TwentyFourBitsColors, ConversionOptions::default());
+    assert_eq!(twenty_four_bits_colors.get_pixel_at(0, 0), Some(Pixel::TwentyFourBitsColors(0, 0, 0)));
+    assert_eq!(twenty_four_bits_colors.get_pixel_at(1, 2), Some(Pixel::TwentyFourBitsColors(255, 255, 255)));
+}
+
+#[test]
+fn converting_to_the_same_pixel_type_keeps_the_image() {
+    for pixel_type in PIXEL_TYPES {
+        let image = colorful_image().convert(pixel_type, ConversionOptions::default());
+
+        assert_eq!(image.convert(pixel_type, ConversionOptions::default()).to_bytes(), image.to_bytes());
+    }
+}
@@ -0,0 +1,70 @@
+use mini_png::conversion::ConversionOptions;
+use mini_png::dithering::{self, Dithering};
+use mini_png::mini_png::MiniPNG;
+use mini_png::pixel::{Pixel, PixelType};
+
+const BLACK_AND_WHITE: [(u8, u8, u8); 2] = [(0, 0, 0), (255, 255, 255)];
+const DITHERINGS: [Dithering; 4] = [Dithering::FloydSteinberg, Dithering::Atkinson, Dithering::Bayer4x4, Dithering::Bayer8x8];
+
+fn whites_count(indexes: &[u8]) -> usize {
+    indexes.iter().filter(|index| **index == 1).count()
+}
+
+#[test]
+fn flat_grays_are_rendered_with_a_matching_proportion_of_white() {
+    let mid_gray = vec![(127, 127, 127); 64];
+    assert_eq!(whites_count(&dithering::dither(&mid_gray, 8, &BLACK_AND_WHITE, Dithering::None)), 0);
+
+    for dithering in DITHERINGS {
+        assert_eq!(whites_count(&dithering::dither(&mid_gray, 8, &BLACK_AND_WHITE, dithering)), 32, "{:?}", dithering);
+    }
+
+    //Atkinson only spreads 3/4 of the error, so darker areas get less white
+    let dark_gray = vec![(64, 64, 64); 64];
+    for dithering in DITHERINGS {
+        let whites_count = whites_count(&dithering::dither(&dark_gray, 8, &BLACK_AND_WHITE, dithering));
+
+        assert!((8..=17).contains(&whites_count), "{:?} gave {} white pixels", dithering, whites_count);
+    }
+}
+
+#[test]
+fn colors_of_the_palette_are_kept() {
+    let palette = [(0, 0, 0), (255, 0, 0), (0, 0, 255)];
+    let colors: Vec<(u8, u8, u8)> = (0..36).map(|i| palette[i * 7 % 3]).collect();
+    let expected: Vec<u8> = (0..36).map(|i| (i * 7 % 3) as u8).collect();
+
+    for dithering in DITHERINGS {
+        assert_eq!(dithering::dither(&colors, 6, &palette, dithering), expected, "{:?}", dithering);
+    }
+}
+
+#[test]
+fn ordered_dithering_repeats_its_matrix() {
+    let colors = vec![(90, 90, 90); 16 * 16];
+
+    for (dithering, size) in [(Dithering::Bayer4x4, 4), (Dithering::Bayer8x8, 8)] {
+        let indexes = dithering::dither(&colors, 16, &BLACK_AND_WHITE, dithering);
+
+        for x in 0..16 - size {
+            for y in 0..16 - size {
+                assert_eq!(indexes[16 * x + y], indexes[16 * (x + size) + y]);
+                assert_eq!(indexes[16 * x + y], indexes[16 * x + y + size]);
+            }
+        }
+    }
+}
+
+#[test]
+fn dithered_conversions_give_valid_images() {
+    let image = MiniPNG::from_pixels(16, 16, (0..256).map(|i| Pixel::TwentyFourBitsColors(i as u8, (255 - i) as u8, (i * 3 % 256) as u8)).collect()).unwrap();
+
+    for dithering in DITHERINGS {
+        for pixel_type in [PixelType::BlackAndWhite, PixelType::Palette] {
+            let converted = image.convert(pixel_type, ConversionOptions { max_palette_entries: 4, dithering, ..ConversionOptions::default() });
+
+            assert_eq!(converted.get_pixel_type(), pixel_type);
+            assert!(MiniPNG::from_bytes(&converted.to_bytes()).is_ok());
+        }
+    }
+}
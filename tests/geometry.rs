@@ -0,0 +1,70 @@
+use mini_png::mini_png::MiniPNG;
+use mini_png::pixel::Pixel;
+
+//a 3x2 gray image whose pixel at row x and column y has the value 10 * x + y
+fn numbered() -> MiniPNG {
+    MiniPNG::from_pixels(3, 2, vec![Pixel::Gray(0), Pixel::Gray(1), Pixel::Gray(2), Pixel::Gray(10), Pixel::Gray(11), Pixel::Gray(12)]).unwrap()
+}
+
+//the gray levels row by row
+fn values(image: &MiniPNG) -> Vec<Vec<u8>> {
+    (0..image.get_image_height()).map(|x| (0..image.get_image_width()).map(|y| match image.get_pixel_at(x, y) {
+                                                                          Some(Pixel::Gray(value)) => value,
+                                                                          pixel => panic!("unexpected pixel {:?}", pixel)
+                                                                      })
+                                                                      .collect())
+                                 .collect()
+}
+
+#[test]
+fn transforms_move_the_pixels() {
+    let image = numbered();
+
+    assert_eq!(values(&image.flip_horizontal()), [[2, 1, 0], [12, 11, 10]]);
+    assert_eq!(values(&image.flip_vertical()), [[10, 11, 12], [0, 1, 2]]);
+    assert_eq!(values(&image.transpose()), [[0, 10], [1, 11], [2, 12]]);
+    assert_eq!(values(&image.rotate90()), [[10, 0], [11, 1], [12, 2]]);
+    assert_eq!(values(&image.rotate180()), [[12, 11, 10], [2, 1, 0]]);
+    assert_eq!(values(&image.rotate270()), [[2, 12], [1, 11], [0, 10]]);
+}
+
+#[test]
+fn rotations_swap_the_dimensions() {
+    let image = numbered();
+
+    for rotated in [image.rotate90(), image.rotate270(), image.transpose()] {
+        assert_eq!((rotated.get_image_width(), rotated.get_image_height()), (2, 3));
+    }
+
+    assert_eq!(values(&image.rotate90().rotate90().rotate90().rotate90()), values(&image));
+    assert_eq!(values(&image.rotate90().rotate270()), values(&image));
+}
+
+#[test]
+fn crops_must_stay_within_the_image() {
+    let image = numbered();
+
+    let cropped = image.crop(1, 1, 2, 1).unwrap();
+    assert_eq!((cropped.get_image_width(), cropped.get_image_height()), (2, 1));
+    assert_eq!(values(&cropped), [[11, 12]]);
+
+    assert!(image.crop(0, 0, 0, 1).is_none());
+    assert!(image.crop(1, 0, 3, 2).is_none());
+    assert!(image.crop(0, 2, 2, 1).is_none());
+    assert!(image.crop(u32::MAX, 0, 1, 1).is_none());
+}
+
+#[test]
+fn black_and_white_images_are_packed_again() {
+    //9 pixels wide, so that the rows of the rotated image no longer start on a byte boundary
+    let pixels = (0..18).map(|i| if i % 4 == 0 { Pixel::Black } else { Pixel::White }).collect();
+    let image = MiniPNG::from_pixels(9, 2, pixels).unwrap();
+
+    let rotated = MiniPNG::from_bytes(&image.rotate90().to_bytes()).unwrap();
+
+    for x in 0..2 {
+        for y in 0..9 {
+            assert_eq!(rotated.get_pixel_at(y, 1 - x), image.get_pixel_at(x, y));
+        }
+    }
+}
@@ -0,0 +1,370 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use flate2::write::ZlibEncoder;
+
+use mini_png::block::{Block, BlockContent, DEFAULT_MAX_DATA_LENGTH};
+use mini_png::blocks::Blocks;
+use mini_png::decode_options::{DecodeOptions, Diagnostic, Recovery};
+use mini_png::encode_options::{Compression, EncodeOptions};
+use mini_png::error::MiniPngError;
+use mini_png::mini_png::MiniPNG;
+use mini_png::pixel::Pixel;
+
+//the magic followed by the given blocks, each one given as its type and content
+fn mini_png_bytes(blocks: &[(u8, &[u8])]) -> Vec<u8> {
+    let mut bytes = b"Mini-PNG".to_vec();
+
+    for (type_byte, content) in blocks {
+        bytes.push(*type_byte);
+        bytes.extend_from_slice(&(content.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(content);
+    }
+
+    bytes
+}
+
+fn header(image_width: u32, image_height: u32, pixel_type: u8) -> Vec<u8> {
+    let mut bytes = image_width.to_be_bytes().to_vec();
+    bytes.extend_from_slice(&image_height.to_be_bytes());
+    bytes.push(pixel_type);
+
+    bytes
+}
+
+fn zlib(bytes: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::best());
+    encoder.write_all(bytes).unwrap();
+
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn compressed_blocks_cannot_inflate_beyond_the_image_size() {
+    let bomb = zlib(&vec![0; 16 * 1024 * 1024]);
+    let bytes = mini_png_bytes(&[(b'H', &header(1, 1, 1)), (b'Z', &bomb)]);
+
+    //the content of the Z block starts after the magic (8 bytes), the header block (5 + 9 bytes) and its own type and length (5 bytes)
+    assert!(matches!(MiniPNG::from_bytes(&bytes), Err(MiniPngError::DecompressedTooLarge { limit: 1, offset: 27 })));
+
+    let (image, diagnostics) = MiniPNG::from_bytes_with_options(&bytes, &DecodeOptions::lenient()).unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(diagnostics[0], Diagnostic { error: MiniPngError::DecompressedTooLarge { limit: 1, offset: 27 }, recovery: Recovery::Truncated }));
+    assert_eq!(image.get_pixel_at(0, 0), Some(Pixel::Gray(0)));
+}
+
+#[test]
+fn parsed_blocks_cannot_inflate_beyond_the_default_limit() {
+    let bomb = zlib(&vec![0; DEFAULT_MAX_DATA_LENGTH + 1]);
+    let bytes = mini_png_bytes(&[(b'Z', &bomb)]);
+    let raw_block = Blocks::new(&bytes).unwrap().next().unwrap().unwrap();
+
+    assert!(matches!(raw_block.parse(), Err(MiniPngError::DecompressedTooLarge { limit: DEFAULT_MAX_DATA_LENGTH, offset: 13 })));
+    assert!(matches!(Block::try_from(raw_block.bytes()), Err(MiniPngError::DecompressedTooLarge { limit: DEFAULT_MAX_DATA_LENGTH, offset: 5 })));
+    assert!(matches!(raw_block.parse_with_limit(10), Err(MiniPngError::DecompressedTooLarge { limit: 10, offset: 13 })));
+}
+
+#[test]
+fn compressed_blocks_are_limited_by_the_data_already_read() {
+    let bytes = mini_png_bytes(&[(b'H', &header(2, 1, 1)), (b'D', &[1, 2]), (b'Z', &zlib(&[3]))]);
+
+    assert!(matches!(MiniPNG::from_bytes(&bytes), Err(MiniPngError::DecompressedTooLarge { limit: 0, offset: 34 })));
+
+    //the extra data is dropped as it would be in a D block, and the valid pixels are kept
+    let options = DecodeOptions { preserve_layout: true, ..DecodeOptions::lenient() };
+    let (image, diagnostics) = MiniPNG::from_bytes_with_options(&bytes, &options).unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(diagnostics[0], Diagnostic { error: MiniPngError::DecompressedTooLarge { limit: 0, offset: 34 }, recovery: Recovery::Truncated }));
+    assert_eq!(image.get_pixel_at(0, 1), Some(Pixel::Gray(2)));
+
+    let reencoded = MiniPNG::from_bytes(&image.to_bytes()).unwrap();
+    assert_eq!(reencoded.get_pixel_at(0, 1), Some(Pixel::Gray(2)));
+}
+
+#[test]
+fn duplicate_palettes_are_rejected_in_strict_mode_for_any_pixel_type() {
+    let bytes = mini_png_bytes(&[(b'H', &header(1, 1, 1)), (b'D', &[7]), (b'P', &[1, 2, 3]), (b'P', &[4, 5, 6])]);
+
+    //the second palette block starts after the magic (8 bytes), the header block (5 + 9 bytes), the data block (5 + 1 bytes) and the first palette block (5 + 3 bytes)
+    assert!(matches!(MiniPNG::from_bytes(&bytes), Err(MiniPngError::PaletteCount { count: 2, offset: 36 })));
+
+    let (_, diagnostics) = MiniPNG::from_bytes_with_options(&bytes, &DecodeOptions::lenient()).unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(diagnostics[0], Diagnostic { error: MiniPngError::PaletteCount { count: 2, offset: 36 }, recovery: Recovery::Skipped }));
+}
+
+#[test]
+fn sample_files_are_written_back_byte_for_byte() {
+    let bytes = fs::read("G/G.mp").unwrap();
+    let image = MiniPNG::from_bytes(&bytes).unwrap();
+
+    assert_eq!(image.to_bytes(), bytes);
+
+    let mut written = Vec::new();
+    image.write_to(&mut written).unwrap();
+    assert_eq!(written, bytes);
+}
+
+#[test]
+fn saved_files_can_be_loaded_back() {
+    let image = MiniPNG::from_file(Path::new("G/G.mp")).unwrap();
+
+    let file_path = std::env::temp_dir().join(format!("mini_png_encoder_{}.mp", std::process::id()));
+    image.save(&file_path).unwrap();
+    let saved = fs::read(&file_path);
+    fs::remove_file(&file_path).unwrap();
+
+    assert_eq!(saved.unwrap(), fs::read("G/G.mp").unwrap());
+}
+
+#[test]
+fn malformed_files_are_rejected_with_the_offset_of_the_faulty_bytes() {
+    let header_block: &[u8] = &header(1, 1, 1);
+
+    //the block following the header starts at byte 22, and its length at byte 23
+    let mut truncated = mini_png_bytes(&[(b'H', header_block)]);
+    truncated.extend_from_slice(b"D\x00\x00\x00\x04\x07");
+    let error = MiniPNG::from_bytes(&truncated).err();
+    assert!(matches!(error, Some(MiniPngError::TruncatedBlock { offset: 22 })));
+    assert_eq!(error.and_then(|error| error.offset()), Some(22));
+
+    let zero_length = mini_png_bytes(&[(b'H', header_block), (b'C', &[]), (b'D', &[7])]);
+    assert!(matches!(MiniPNG::from_bytes(&zero_length), Err(MiniPngError::ZeroLengthBlock { offset: 23 })));
+
+    assert!(matches!(MiniPNG::from_bytes(b"Mini-PNH"), Err(MiniPngError::BadMagic { offset: 0 })));
+    assert!(matches!(MiniPNG::from_bytes(b"Mini"), Err(MiniPngError::BadMagic { offset: 0 })));
+
+    //a missing block is reported at the end of the file
+    let missing_header = mini_png_bytes(&[(b'D', &[7])]);
+    assert!(matches!(MiniPNG::from_bytes(&missing_header), Err(MiniPngError::HeaderCount { count: 0, offset: 14 })));
+
+    let missing_data = mini_png_bytes(&[(b'H', header_block)]);
+    assert!(matches!(MiniPNG::from_bytes(&missing_data), Err(MiniPngError::MissingData { offset: 22 })));
+}
+
+#[test]
+fn lenient_decoding_reports_how_each_deviation_was_recovered() {
+    let header_block: &[u8] = &header(2, 1, 1);
+    let mut bytes = mini_png_bytes(&[(b'H', header_block), (b'C', b"a\xff"), (b'X', &[0]), (b'H', header_block), (b'D', &[7])]);
+    bytes.extend_from_slice(b"D\x00\x00"); //trailing bytes too short to be a block
+
+    assert!(matches!(MiniPNG::from_bytes(&bytes), Err(MiniPngError::NonAsciiComment { offset: 28 })));
+
+    let (image, diagnostics) = MiniPNG::from_bytes_with_options(&bytes, &DecodeOptions::lenient()).unwrap();
+    assert_eq!(diagnostics.len(), 5);
+    assert!(matches!(diagnostics[0], Diagnostic { error: MiniPngError::NonAsciiComment { offset: 28 }, recovery: Recovery::Repaired }));
+    assert!(matches!(diagnostics[1], Diagnostic { error: MiniPngError::UnknownBlockType { type_byte: b'X', offset: 29 }, recovery: Recovery::Skipped }));
+    assert!(matches!(diagnostics[2], Diagnostic { error: MiniPngError::TruncatedBlock { offset: 55 }, recovery: Recovery::Skipped }));
+    assert!(matches!(diagnostics[3], Diagnostic { error: MiniPngError::HeaderCount { count: 2, offset: 35 }, recovery: Recovery::Skipped }));
+    assert!(matches!(diagnostics[4], Diagnostic { error: MiniPngError::SizeMismatch { expected: 2, actual: 1, offset: 49 }, recovery: Recovery::Padded }));
+
+    assert_eq!(image.get_comments(), ["a?"]);
+    assert_eq!(image.get_pixel_at(0, 0), Some(Pixel::Gray(7)));
+    assert_eq!(image.get_pixel_at(0, 1), Some(Pixel::Gray(0)));
+}
+
+#[test]
+fn lenient_decoding_truncates_extra_pixel_data() {
+    let bytes = mini_png_bytes(&[(b'H', &header(2, 1, 1)), (b'D', &[7, 8, 9])]);
+
+    assert!(matches!(MiniPNG::from_bytes(&bytes), Err(MiniPngError::SizeMismatch { expected: 2, actual: 3, offset: 22 })));
+
+    let (image, diagnostics) = MiniPNG::from_bytes_with_options(&bytes, &DecodeOptions::lenient()).unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(diagnostics[0], Diagnostic { error: MiniPngError::SizeMismatch { .. }, recovery: Recovery::Truncated }));
+    assert_eq!(image.get_pixel_at(0, 1), Some(Pixel::Gray(8)));
+    assert_eq!(image.to_bytes(), mini_png_bytes(&[(b'H', &header(2, 1, 1)), (b'D', &[7, 8])]));
+}
+
+#[test]
+fn compressed_images_decode_to_the_same_pixels() {
+    let image = MiniPNG::from_file(Path::new("G/G.mp")).unwrap();
+
+    let compressed = image.to_bytes_with_options(&EncodeOptions { compression: Compression::Deflate, ..EncodeOptions::default() });
+    let block_types: Vec<u8> = Blocks::new(&compressed).unwrap().map(|raw_block| raw_block.unwrap().type_byte()).collect();
+    assert_eq!(block_types, b"HCZ");
+
+    assert_eq!(MiniPNG::from_bytes(&compressed).unwrap().to_bytes(), fs::read("G/G.mp").unwrap());
+}
+
+#[test]
+fn compressed_blocks_written_by_other_encoders_are_read() {
+    let bytes = mini_png_bytes(&[(b'H', &header(2, 2, 1)), (b'Z', &zlib(&[1, 2])), (b'D', &[3]), (b'Z', &zlib(&[4]))]);
+
+    let image = MiniPNG::from_bytes(&bytes).unwrap();
+
+    assert_eq!(image.get_pixel_at(0, 1), Some(Pixel::Gray(2)));
+    assert_eq!(image.get_pixel_at(1, 0), Some(Pixel::Gray(3)));
+    assert_eq!(image.get_pixel_at(1, 1), Some(Pixel::Gray(4)));
+}
+
+#[test]
+fn invalid_compressed_data_is_rejected() {
+    let bytes = mini_png_bytes(&[(b'H', &header(1, 1, 1)), (b'Z', b"not zlib")]);
+
+    assert!(matches!(MiniPNG::from_bytes(&bytes), Err(MiniPngError::InvalidCompressedData { offset: 27 })));
+}
+
+#[test]
+fn every_block_can_be_followed_by_its_checksum() {
+    let bytes = MiniPNG::from_file(Path::new("G/G.mp")).unwrap()
+                                                       .to_bytes_with_options(&EncodeOptions { checksums: true, ..EncodeOptions::default() });
+
+    let raw_blocks: Vec<_> = Blocks::new(&bytes).unwrap().map(|raw_block| raw_block.unwrap()).collect();
+    assert_eq!(raw_blocks.iter().map(|raw_block| raw_block.type_byte()).collect::<Vec<u8>>(), b"HKCKDK");
+
+    for pair in raw_blocks.chunks(2) {
+        assert_eq!(pair[1].content(), crc32fast::hash(pair[0].bytes()).to_be_bytes());
+    }
+
+    assert_eq!(MiniPNG::from_bytes(&bytes).unwrap().to_bytes(), fs::read("G/G.mp").unwrap());
+}
+
+#[test]
+fn corrupted_blocks_are_detected_by_their_checksum() {
+    let mut bytes = MiniPNG::from_file(Path::new("G/G.mp")).unwrap()
+                                                           .to_bytes_with_options(&EncodeOptions { checksums: true, ..EncodeOptions::default() });
+
+    //the comment block starts after the magic (8 bytes), the header block (5 + 9 bytes) and its checksum block (5 + 4 bytes)
+    bytes[36] = b'g';
+
+    assert!(matches!(MiniPNG::from_bytes(&bytes), Err(MiniPngError::ChecksumMismatch { type_byte: b'C', offset: 31 })));
+
+    let (image, diagnostics) = MiniPNG::from_bytes_with_options(&bytes, &DecodeOptions::lenient()).unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(diagnostics[0], Diagnostic { error: MiniPngError::ChecksumMismatch { type_byte: b'C', offset: 31 }, recovery: Recovery::Kept }));
+    assert_eq!(image.get_comments(), ["g comme GOAT"]);
+}
+
+#[test]
+fn checksum_blocks_must_hold_4_bytes() {
+    let bytes = mini_png_bytes(&[(b'H', &header(1, 1, 1)), (b'K', &[0; 3]), (b'D', &[7])]);
+
+    assert!(matches!(MiniPNG::from_bytes(&bytes), Err(MiniPngError::InvalidChecksumLength { length: 3, offset: 27 })));
+}
+
+#[test]
+fn ancillary_blocks_are_preserved() {
+    let bytes = mini_png_bytes(&[(b'H', &header(1, 1, 1)), (b'C', b"kept"), (b't', b"\x00\xffany bytes"), (b'x', &[1]), (b'D', &[7])]);
+
+    let image = MiniPNG::from_bytes(&bytes).unwrap();
+
+    assert_eq!(image.get_unknown_blocks(), [(b't', b"\x00\xffany bytes".to_vec()), (b'x', vec![1])]);
+    assert_eq!(image.to_bytes(), bytes);
+}
+
+#[test]
+fn unknown_critical_blocks_are_rejected() {
+    let bytes = mini_png_bytes(&[(b'H', &header(1, 1, 1)), (b'X', &[1]), (b'D', &[7])]);
+
+    assert!(matches!(MiniPNG::from_bytes(&bytes), Err(MiniPngError::UnknownBlockType { type_byte: b'X', offset: 22 })));
+}
+
+#[test]
+fn block_layouts_are_preserved_byte_for_byte() {
+    let compressed = zlib(&[2, 3]);
+    let mut compressed_block = vec![b'Z'];
+    compressed_block.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+    compressed_block.extend_from_slice(&compressed);
+
+    //split data, comments between data blocks, an ancillary block and a checksum, in an order the encoder would not choose
+    let bytes = mini_png_bytes(&[(b'H', &header(2, 2, 1)), (b'D', &[1]), (b'C', b"first"), (b'Z', &compressed), (b'K', &crc32fast::hash(&compressed_block).to_be_bytes()),
+                                 (b'x', &[9]), (b'D', &[4]), (b'C', b"second")]);
+
+    assert_ne!(MiniPNG::from_bytes(&bytes).unwrap().to_bytes(), bytes);
+
+    let options = DecodeOptions { preserve_layout: true, ..DecodeOptions::default() };
+    let (mut image, _) = MiniPNG::from_bytes_with_options(&bytes, &options).unwrap();
+    assert_eq!(image.to_bytes(), bytes);
+
+    let sample = fs::read("G/G.mp").unwrap();
+    assert_eq!(MiniPNG::from_bytes_with_options(&sample, &options).unwrap().0.to_bytes(), sample);
+
+    //the layout is kept as long as it can describe the image
+    image.set_pixel_at(1, 1, Pixel::Gray(5)).unwrap();
+    let modified = image.to_bytes();
+    let block_types: Vec<u8> = Blocks::new(&modified).unwrap().map(|raw_block| raw_block.unwrap().type_byte()).collect();
+    assert_eq!(block_types, b"HDCZKxDC");
+    assert_eq!(MiniPNG::from_bytes(&modified).unwrap().get_pixel_at(1, 1), Some(Pixel::Gray(5)));
+
+    image.add_comment("third").unwrap();
+    let block_types: Vec<u8> = Blocks::new(&image.to_bytes()).unwrap().map(|raw_block| raw_block.unwrap().type_byte()).collect();
+    assert_eq!(block_types, b"HCCCxD");
+}
+
+#[test]
+fn blocks_are_listed_with_their_position() {
+    let bytes = fs::read("G/G.mp").unwrap();
+    let mut blocks = Blocks::new(&bytes).unwrap();
+    assert_eq!(blocks.offset(), 8);
+
+    let raw_blocks: Vec<(usize, u8, u32)> = blocks.by_ref()
+                                                  .map(|raw_block| raw_block.unwrap())
+                                                  .map(|raw_block| (raw_block.offset(), raw_block.type_byte(), raw_block.block_length()))
+                                                  .collect();
+
+    assert_eq!(raw_blocks, [(8, b'H', 9), (22, b'C', 12), (39, b'D', 8)]);
+    assert_eq!(blocks.offset(), bytes.len());
+}
+
+#[test]
+fn iteration_stops_after_a_framing_error() {
+    let mut truncated = fs::read("G/G.mp").unwrap();
+    truncated.extend_from_slice(b"D\x00\x00\x00\x05\x01");
+
+    let mut blocks = Blocks::new(&truncated).unwrap().skip(3);
+    assert!(matches!(blocks.next(), Some(Err(MiniPngError::TruncatedBlock { offset: 52 }))));
+    assert!(blocks.next().is_none());
+
+    let zero_length = mini_png_bytes(&[(b'H', &header(1, 1, 1)), (b'C', &[]), (b'D', &[7])]);
+    let results: Vec<_> = Blocks::new(&zero_length).unwrap().collect();
+    assert_eq!(results.len(), 2);
+    assert!(matches!(results[1], Err(MiniPngError::ZeroLengthBlock { offset: 23 })));
+}
+
+#[test]
+fn blocks_cannot_be_listed_without_the_magic() {
+    assert!(matches!(Blocks::new(b"Mini-PNH\x00"), Err(MiniPngError::BadMagic { offset: 0 })));
+    assert!(matches!(Blocks::new(b""), Err(MiniPngError::BadMagic { offset: 0 })));
+}
+
+#[test]
+fn parse_errors_are_located_in_the_file() {
+    let bytes = mini_png_bytes(&[(b'C', b"first"), (b'H', &[0; 8]), (b'D', &[7])]);
+    let raw_blocks: Vec<_> = Blocks::new(&bytes).unwrap().map(|raw_block| raw_block.unwrap()).collect();
+
+    assert!(matches!(raw_blocks[0].parse().map(|block| block.content), Ok(BlockContent::Comment(_))));
+
+    //the header block starts after the magic (8 bytes) and the comment block (5 + 5 bytes), its content 5 bytes later
+    assert_eq!(raw_blocks[1].offset(), 18);
+    assert!(matches!(raw_blocks[1].parse(), Err(MiniPngError::InvalidHeaderLength { length: 8, offset: 23 })));
+}
+
+#[test]
+fn images_can_be_read_from_any_reader() {
+    let bytes = fs::read("G/G.mp").unwrap();
+    let expected = MiniPNG::from_bytes(&bytes).unwrap();
+
+    let from_file = MiniPNG::from_reader(fs::File::open("G/G.mp").unwrap()).unwrap();
+    let from_slice = MiniPNG::from_reader(&bytes[..]).unwrap();
+
+    for image in [from_file, from_slice] {
+        assert_eq!(image.get_comments(), expected.get_comments());
+        assert_eq!((image.get_image_width(), image.get_image_height()), (8, 8));
+        assert_eq!(image.to_bytes(), expected.to_bytes());
+    }
+
+    assert!(matches!(MiniPNG::from_reader(&b"Mini-PNH"[..]), Err(MiniPngError::BadMagic { offset: 0 })));
+}
+
+#[test]
+fn error_messages_end_after_the_offset() {
+    let header_block: &[u8] = &header(1, 1, 1);
+    let mut truncated = mini_png_bytes(&[(b'H', header_block)]);
+    truncated.extend_from_slice(b"D\x00\x00\x00\x04\x07");
+
+    assert_eq!(MiniPNG::from_bytes(&truncated).err().map(|error| error.to_string()).as_deref(),
+               Some("Unable to parse a block: there is a mismatch between block length and the actual number of bytes (at byte 22)."));
+    assert_eq!(MiniPngError::EmptyComment.to_string(), "Unable to add the comment: it is empty, and blocks cannot have a length of 0.");
+}
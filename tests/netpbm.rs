@@ -0,0 +1,78 @@
+mod common;
+
+use mini_png::error::MiniPngError;
+use mini_png::mini_png::MiniPNG;
+use mini_png::netpbm::{self, NetpbmEncoding};
+use mini_png::pixel::{Pixel, PixelType};
+
+#[test]
+fn empty_comments_are_dropped() {
+    let image = netpbm::from_netpbm(&b"P2\n#\n# kept\n#   \n1 1\n255\n7\n"[..]).unwrap();
+
+    assert_eq!(image.get_comments(), ["kept"]);
+    assert!(MiniPNG::from_bytes(&image.to_bytes()).is_ok());
+}
+
+#[test]
+fn huge_dimensions_are_rejected_before_allocating() {
+    for header in [&b"P5 4000000000 4000000000 255\n"[..], b"P6 100000 100000 65535\n\x00\x00", b"P4 4000000000 4000000000\n", b"P1 100000 100000\n0 1"] {
+        assert!(matches!(netpbm::from_netpbm(header), Err(MiniPngError::InvalidNetpbm { .. })));
+    }
+}
+
+#[test]
+fn images_of_every_pixel_type_survive_a_netpbm_round_trip() {
+    for encoding in [NetpbmEncoding::Ascii, NetpbmEncoding::Binary] {
+        //10 pixels wide, so that the P4 rows take two bytes
+        common::assert_round_trips(10, 2, |image| {
+            let mut bytes = Vec::new();
+            netpbm::to_netpbm(image, &mut bytes, encoding).unwrap();
+
+            let magic_number = match (image.get_pixel_type(), encoding) {
+                (PixelType::BlackAndWhite, NetpbmEncoding::Ascii) => "P1",
+                (PixelType::GrayLevels, NetpbmEncoding::Ascii) => "P2",
+                (_, NetpbmEncoding::Ascii) => "P3",
+                (PixelType::BlackAndWhite, NetpbmEncoding::Binary) => "P4",
+                (PixelType::GrayLevels, NetpbmEncoding::Binary) => "P5",
+                (_, NetpbmEncoding::Binary) => "P6"
+            };
+            assert!(bytes.starts_with(magic_number.as_bytes()));
+
+            netpbm::from_netpbm(&bytes[..]).unwrap()
+        });
+    }
+}
+
+#[test]
+fn black_pixels_are_written_as_ones() {
+    let image = MiniPNG::from_pixels(3, 1, vec![Pixel::Black, Pixel::White, Pixel::Black]).unwrap();
+
+    let mut ascii = Vec::new();
+    netpbm::to_netpbm(&image, &mut ascii, NetpbmEncoding::Ascii).unwrap();
+    assert_eq!(ascii, b"P1\n3 1\n101\n");
+
+    let mut binary = Vec::new();
+    netpbm::to_netpbm(&image, &mut binary, NetpbmEncoding::Binary).unwrap();
+    assert_eq!(binary, b"P4\n3 1\n\xa0");
+}
+
+#[test]
+fn comments_survive_a_netpbm_round_trip() {
+    let mut image = MiniPNG::new(1, 1, PixelType::GrayLevels).unwrap();
+    image.add_comment("first").unwrap();
+    image.add_comment("second").unwrap();
+
+    for encoding in [NetpbmEncoding::Ascii, NetpbmEncoding::Binary] {
+        let mut bytes = Vec::new();
+        netpbm::to_netpbm(&image, &mut bytes, encoding).unwrap();
+
+        assert_eq!(netpbm::from_netpbm(&bytes[..]).unwrap().get_comments(), ["first", "second"]);
+    }
+}
+
+#[test]
+fn zero_dimensions_are_rejected() {
+    for bytes in [&b"P2 0 1 255\n"[..], b"P4 1 0\n"] {
+        assert!(matches!(netpbm::from_netpbm(bytes), Err(MiniPngError::InvalidNetpbm { reason: "the dimensions must be greater than 0", .. })));
+    }
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use mini_png::error::MiniPngError;
+use mini_png::mini_png::MiniPNG;
+use mini_png::pixel::{Pixel, PixelType};
+use mini_png::png_codec;
+
+#[test]
+fn empty_comments_are_dropped() {
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut bytes, 1, 1);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.add_text_chunk(String::from("Comment"), String::new()).unwrap();
+        encoder.add_text_chunk(String::from("Comment"), String::from("kept")).unwrap();
+        encoder.write_header().unwrap().write_image_data(&[7]).unwrap();
+    }
+
+    let image = png_codec::from_png(&bytes[..]).unwrap();
+
+    assert_eq!(image.get_comments(), ["kept"]);
+    assert!(MiniPNG::from_bytes(&image.to_bytes()).is_ok());
+}
+
+#[test]
+fn pixel_types_are_mapped_to_the_most_compact_png_color_type() {
+    let expected = [(png::ColorType::Grayscale, png::BitDepth::One), (png::ColorType::Grayscale, png::BitDepth::Eight), (png::ColorType::Indexed, png::BitDepth::Eight), (png::ColorType::Rgb, png::BitDepth::Eight)];
+
+    for (image, expected) in common::sample_images(3, 2).iter().zip(expected) {
+        let mut bytes = Vec::new();
+        png_codec::to_png(image, &mut bytes).unwrap();
+
+        let reader = png::Decoder::new(&bytes[..]).read_info().unwrap();
+        assert_eq!((reader.info().color_type, reader.info().bit_depth), expected);
+    }
+}
+
+#[test]
+fn images_of_every_pixel_type_survive_a_png_round_trip() {
+    //3 pixels wide, so that the black and white rows are padded in PNG files
+    common::assert_round_trips(3, 2, |image| {
+        let mut bytes = Vec::new();
+        png_codec::to_png(image, &mut bytes).unwrap();
+
+        let imported = png_codec::from_png(&bytes[..]).unwrap();
+        assert_eq!(imported.get_pixel_type(), image.get_pixel_type());
+
+        imported
+    });
+}
+
+//a single row PNG file holding the given samples
+fn png_bytes(image_width: u32, color_type: png::ColorType, bit_depth: png::BitDepth, trns: Option<&[u8]>, data: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut bytes, image_width, 1);
+        encoder.set_color(color_type);
+        encoder.set_depth(bit_depth);
+        if let Some(trns) = trns {
+            encoder.set_trns(trns.to_vec());
+        }
+        encoder.write_header().unwrap().write_image_data(data).unwrap();
+    }
+
+    bytes
+}
+
+#[test]
+fn transparency_and_16_bits_samples_are_rejected() {
+    let unsupported = [
+        png_bytes(1, png::ColorType::Rgba, png::BitDepth::Eight, None, &[1, 2, 3, 4]),
+        png_bytes(1, png::ColorType::GrayscaleAlpha, png::BitDepth::Eight, None, &[1, 2]),
+        png_bytes(1, png::ColorType::Rgb, png::BitDepth::Eight, Some(&[0, 1, 0, 2, 0, 3]), &[1, 2, 3]),
+        png_bytes(1, png::ColorType::Grayscale, png::BitDepth::Sixteen, None, &[1, 2])
+    ];
+
+    for (bytes, feature) in unsupported.iter().zip(["transparency", "transparency", "transparency", "16 bits samples"]) {
+        match png_codec::from_png(&bytes[..]) {
+            Err(MiniPngError::UnsupportedPngFeature(unsupported_feature)) => assert_eq!(unsupported_feature, feature),
+            Err(error) => panic!("unexpected error {}", error),
+            Ok(_) => panic!("the PNG file should have been rejected")
+        }
+    }
+}
+
+#[test]
+fn narrow_gray_samples_are_scaled_to_8_bits() {
+    let two_bits = png_codec::from_png(&png_bytes(4, png::ColorType::Grayscale, png::BitDepth::Two, None, &[0b00_01_10_11])[..]).unwrap();
+    let four_bits = png_codec::from_png(&png_bytes(3, png::ColorType::Grayscale, png::BitDepth::Four, None, &[0x07, 0xf0])[..]).unwrap();
+
+    assert_eq!(two_bits.get_pixel_type(), PixelType::GrayLevels);
+    assert_eq!((0..4).map(|y| two_bits.get_pixel_at(0, y).unwrap()).collect::<Vec<Pixel>>(), [Pixel::Gray(0), Pixel::Gray(85), Pixel::Gray(170), Pixel::Gray(255)]);
+    assert_eq!((0..3).map(|y| four_bits.get_pixel_at(0, y).unwrap()).collect::<Vec<Pixel>>(), [Pixel::Gray(0), Pixel::Gray(119), Pixel::Gray(255)]);
+}
+
+#[test]
+fn comments_survive_a_png_round_trip() {
+    let mut image = MiniPNG::new(1, 1, PixelType::GrayLevels).unwrap();
+    image.add_comment("first").unwrap();
+    image.add_comment("second").unwrap();
+
+    let mut bytes = Vec::new();
+    png_codec::to_png(&image, &mut bytes).unwrap();
+
+    assert_eq!(png_codec::from_png(&bytes[..]).unwrap().get_comments(), ["first", "second"]);
+}
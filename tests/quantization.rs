@@ -0,0 +1,50 @@
+use mini_png::conversion::ConversionOptions;
+use mini_png::mini_png::MiniPNG;
+use mini_png::pixel::{Pixel, PixelType};
+use mini_png::quantization::{self, Quantizer};
+
+//a 32x32 gradient where every pixel has its own color
+fn gradient() -> Vec<(u8, u8, u8)> {
+    (0..32 * 32).map(|i| ((i % 32 * 8) as u8, (i / 32 * 8) as u8, (i % 7 * 36) as u8)).collect()
+}
+
+#[test]
+fn palettes_never_exceed_the_requested_size() {
+    let colors = gradient();
+
+    for quantizer in [Quantizer::MedianCut, Quantizer::Octree] {
+        for max_entries in [1, 2, 16, 255, 256] {
+            let palette = quantization::quantize(&colors, max_entries, quantizer);
+
+            assert!(!palette.is_empty() && palette.len() <= max_entries, "{:?} gave {} entries instead of at most {}", quantizer, palette.len(), max_entries);
+        }
+
+        assert_eq!(quantization::quantize(&colors, 0, quantizer).len(), 1);
+    }
+}
+
+#[test]
+fn colors_are_kept_when_they_fit_in_the_palette() {
+    let colors = [(9, 9, 9), (1, 2, 3), (9, 9, 9), (200, 100, 0)];
+
+    for quantizer in [Quantizer::MedianCut, Quantizer::Octree] {
+        assert_eq!(quantization::quantize(&colors, 3, quantizer), [(1, 2, 3), (9, 9, 9), (200, 100, 0)]);
+    }
+}
+
+#[test]
+fn quantized_images_only_refer_to_existing_entries() {
+    let image = MiniPNG::from_pixels(32, 32, gradient().into_iter().map(|(r, g, b)| Pixel::TwentyFourBitsColors(r, g, b)).collect()).unwrap();
+
+    for quantizer in [Quantizer::MedianCut, Quantizer::Octree] {
+        let converted = image.convert(PixelType::Palette, ConversionOptions { max_palette_entries: 16, quantizer, ..ConversionOptions::default() });
+        let entries_count = converted.get_palette().unwrap().entries().len();
+
+        assert!(entries_count <= 16);
+        for x in 0..32 {
+            for y in 0..32 {
+                assert!(matches!(converted.get_pixel_at(x, y), Some(Pixel::Palette(index)) if (index as usize) < entries_count));
+            }
+        }
+    }
+}
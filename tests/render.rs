@@ -0,0 +1,167 @@
+use std::path::Path;
+
+use mini_png::mini_png::MiniPNG;
+use mini_png::render::{self, Framebuffer, RenderOptions, GLYPH_WIDTH};
+
+//'#' is black, '.' is white and 'o' is the background (red)
+fn to_ascii(framebuffer: &Framebuffer) -> Vec<String> {
+    (0..framebuffer.get_height()).map(|x| (0..framebuffer.get_width()).map(|y| match framebuffer.get_rgb_at(x, y).unwrap() {
+                                                                          (0, 0, 0) => '#',
+                                                                          (255, 255, 255) => '.',
+                                                                          (255, 0, 0) => 'o',
+                                                                          _ => '?'
+                                                                      })
+                                                                      .collect())
+                                 .collect()
+}
+
+fn g() -> MiniPNG {
+    MiniPNG::from_file(Path::new("G/G.mp")).unwrap()
+}
+
+#[test]
+fn renders_g_at_zoom_1() {
+    let framebuffer = render::render(&g(), &RenderOptions::default());
+
+    assert_eq!((framebuffer.get_width(), framebuffer.get_height()), (8, 8));
+    assert_eq!(framebuffer.as_bytes().len(), 8 * 8 * 3);
+    assert_eq!(to_ascii(&framebuffer), [
+        "........",
+        ".######.",
+        ".#......",
+        ".#......",
+        ".#..###.",
+        ".#....#.",
+        ".######.",
+        "........"
+    ]);
+}
+
+#[test]
+fn renders_g_at_zoom_2() {
+    let framebuffer = render::render(&g(), &RenderOptions { zoom: 2, ..RenderOptions::default() });
+
+    assert_eq!((framebuffer.get_width(), framebuffer.get_height()), (16, 16));
+    assert_eq!(to_ascii(&framebuffer)[2..6], [
+        "..############..",
+        "..############..",
+        "..##............",
+        "..##............"
+    ]);
+}
+
+#[test]
+fn renders_g_centered_on_the_background() {
+    let options = RenderOptions { background: (255, 0, 0), viewport: Some((12, 10)), ..RenderOptions::default() };
+    let framebuffer = render::render(&g(), &options);
+
+    assert_eq!(to_ascii(&framebuffer), [
+        "oooooooooooo",
+        "oo........oo",
+        "oo.######.oo",
+        "oo.#......oo",
+        "oo.#......oo",
+        "oo.#..###.oo",
+        "oo.#....#.oo",
+        "oo.######.oo",
+        "oo........oo",
+        "oooooooooooo"
+    ]);
+}
+
+#[test]
+fn crops_g_when_the_viewport_is_too_small() {
+    let options = RenderOptions { background: (255, 0, 0), viewport: Some((4, 2)), ..RenderOptions::default() };
+    let framebuffer = render::render(&g(), &options);
+
+    assert_eq!(to_ascii(&framebuffer), [
+        "....",
+        "..##"
+    ]);
+}
+
+#[test]
+fn renders_g_shrunk() {
+    let framebuffer = render::render(&g(), &RenderOptions { shrink: 2, ..RenderOptions::default() });
+
+    assert_eq!(to_ascii(&framebuffer), [
+        "....",
+        "....",
+        "..##",
+        ".###"
+    ]);
+}
+
+#[test]
+fn renders_g_moved_by_the_offset() {
+    let options = RenderOptions { background: (255, 0, 0), viewport: Some((8, 8)), offset: (3, -5), ..RenderOptions::default() };
+    let framebuffer = render::render(&g(), &options);
+
+    assert_eq!(to_ascii(&framebuffer), [
+        "ooo.#...",
+        "ooo.####",
+        "ooo.....",
+        "oooooooo",
+        "oooooooo",
+        "oooooooo",
+        "oooooooo",
+        "oooooooo"
+    ]);
+}
+
+#[test]
+fn draws_text_over_the_background() {
+    let mut framebuffer = Framebuffer::new(2 * GLYPH_WIDTH, 7, (255, 0, 0));
+    framebuffer.draw_text(0, 0, "hi", (0, 0, 0), 1);
+
+    assert_eq!(to_ascii(&framebuffer), [
+        "#ooo#oo###oo",
+        "#ooo#ooo#ooo",
+        "#ooo#ooo#ooo",
+        "#####ooo#ooo",
+        "#ooo#ooo#ooo",
+        "#ooo#ooo#ooo",
+        "#ooo#oo###oo"
+    ]);
+}
+
+#[test]
+fn wraps_text_between_words() {
+    assert_eq!(render::wrap_text("Unable to parse a block", 10), "Unable to\nparse a\nblock");
+    assert_eq!(render::wrap_text("abcdefghij klm", 4), "abcd\nefgh\nij\nklm");
+}
+
+#[test]
+fn draws_a_grid_between_the_zoomed_pixels() {
+    let options = RenderOptions { zoom: 3, background: (255, 0, 0), viewport: Some((6, 6)), offset: (9, 9), grid: Some((255, 0, 0)), ..RenderOptions::default() };
+    let framebuffer = render::render(&g(), &options);
+
+    assert_eq!(to_ascii(&framebuffer), [
+        "oooooo",
+        "o..o..",
+        "o..o..",
+        "oooooo",
+        "o..o##",
+        "o..o##"
+    ]);
+}
+
+#[test]
+fn finds_the_pixel_under_a_framebuffer_position() {
+    let options = RenderOptions { zoom: 2, viewport: Some((20, 20)), offset: (1, -1), ..RenderOptions::default() };
+    let image = g();
+
+    assert_eq!(render::image_position(&image, &options, 1, 3), Some((0, 0)));
+    assert_eq!(render::image_position(&image, &options, 16, 18), Some((7, 7)));
+    assert_eq!(render::image_position(&image, &options, 0, 3), None);
+    assert_eq!(render::image_position(&image, &options, 1, 19), None);
+}
+
+#[test]
+fn places_the_image_origin_like_the_renderer() {
+    let image = g();
+
+    assert_eq!(render::image_origin(&image, &RenderOptions { viewport: Some((12, 10)), ..RenderOptions::default() }), (1, 2));
+    assert_eq!(render::image_origin(&image, &RenderOptions { viewport: Some((8, 8)), offset: (3, -5), ..RenderOptions::default() }), (-5, 3));
+    assert_eq!(render::image_origin(&image, &RenderOptions { zoom: 2, viewport: Some((20, 20)), offset: (1, -1), ..RenderOptions::default() }), (1, 3));
+}
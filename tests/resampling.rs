@@ -0,0 +1,63 @@
+use mini_png::mini_png::MiniPNG;
+use mini_png::pixel::{Pixel, PixelType};
+use mini_png::resampling::Filter;
+
+const FILTERS: [Filter; 3] = [Filter::Nearest, Filter::Bilinear, Filter::Lanczos];
+
+fn row(image: &MiniPNG, x: u32) -> Vec<Pixel> {
+    (0..image.get_image_width()).map(|y| image.get_pixel_at(x, y).unwrap()).collect()
+}
+
+#[test]
+fn resized_images_have_the_requested_dimensions() {
+    let gray_levels = MiniPNG::from_pixels(3, 2, (0..6).map(|i| Pixel::Gray(i * 40)).collect()).unwrap();
+    let images = [gray_levels.convert(PixelType::BlackAndWhite, Default::default()), gray_levels.convert(PixelType::Palette, Default::default()),
+                  gray_levels.convert(PixelType::TwentyFourBitsColors, Default::default()), gray_levels];
+
+    for image in &images {
+        for filter in FILTERS {
+            for (width, height) in [(1, 1), (7, 5), (2, 9)] {
+                let resized = image.resize(width, height, filter).unwrap();
+
+                assert_eq!((resized.get_image_width(), resized.get_image_height(), resized.get_pixel_type()), (width, height, image.get_pixel_type()));
+                assert!(MiniPNG::from_bytes(&resized.to_bytes()).is_ok());
+            }
+
+            assert!(image.resize(0, 1, filter).is_none());
+            assert!(image.resize(1, 0, filter).is_none());
+        }
+    }
+}
+
+#[test]
+fn filters_interpolate_between_the_pixels() {
+    let image = MiniPNG::from_pixels(2, 1, vec![Pixel::Gray(0), Pixel::Gray(100)]).unwrap();
+
+    assert_eq!(row(&image.resize(4, 1, Filter::Nearest).unwrap(), 0), [Pixel::Gray(0), Pixel::Gray(0), Pixel::Gray(100), Pixel::Gray(100)]);
+    assert_eq!(row(&image.resize(4, 1, Filter::Bilinear).unwrap(), 0), [Pixel::Gray(0), Pixel::Gray(25), Pixel::Gray(75), Pixel::Gray(100)]);
+
+    //Lanczos rings around edges
+    assert_eq!(row(&image.resize(4, 1, Filter::Lanczos).unwrap(), 0), [Pixel::Gray(0), Pixel::Gray(21), Pixel::Gray(79), Pixel::Gray(110)]);
+}
+
+#[test]
+fn flat_images_stay_flat() {
+    let image = MiniPNG::from_pixels(3, 3, vec![Pixel::TwentyFourBitsColors(10, 150, 250); 9]).unwrap();
+
+    for filter in FILTERS {
+        let resized = image.resize(7, 5, filter).unwrap();
+
+        for x in 0..5 {
+            assert!(row(&resized, x).iter().all(|pixel| *pixel == Pixel::TwentyFourBitsColors(10, 150, 250)), "{:?}", filter);
+        }
+    }
+}
+
+#[test]
+fn palette_images_are_always_resized_with_nearest() {
+    let image = MiniPNG::from_pixels(2, 1, vec![Pixel::Palette(0), Pixel::Palette(1)]).unwrap();
+
+    for filter in FILTERS {
+        assert_eq!(row(&image.resize(4, 1, filter).unwrap(), 0), [Pixel::Palette(0), Pixel::Palette(0), Pixel::Palette(1), Pixel::Palette(1)]);
+    }
+}